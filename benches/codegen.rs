@@ -1,10 +1,9 @@
-use cornucopia::{conn::cornucopia_conn, CodegenSettings};
+use cornucopia::{conn::cornucopia_conn, CodegenSettings, ContainerConfig};
 use criterion::Criterion;
 
 fn bench(c: &mut Criterion) {
-    cornucopia::container::cleanup(false).ok();
-    cornucopia::container::setup(false).unwrap();
-    let client = &mut cornucopia_conn().unwrap();
+    let container = cornucopia::container::setup(&ContainerConfig::default()).unwrap();
+    let client = &mut cornucopia_conn(&container).unwrap();
 
     cornucopia::load_schema(client, &["../codegen_test/schema.sql"]).unwrap();
     c.bench_function("codegen_sync", |b| {
@@ -17,6 +16,7 @@ fn bench(c: &mut Criterion) {
                     gen_sync: true,
                     gen_async: false,
                     derive_ser: true,
+                    ..Default::default()
                 },
             )
             .unwrap()
@@ -32,12 +32,13 @@ fn bench(c: &mut Criterion) {
                     gen_sync: true,
                     gen_async: false,
                     derive_ser: true,
+                    ..Default::default()
                 },
             )
             .unwrap()
         })
     });
-    cornucopia::container::cleanup(false).unwrap();
+    container.cleanup().unwrap();
 }
 criterion::criterion_group!(benches, bench);
 criterion::criterion_main!(benches);
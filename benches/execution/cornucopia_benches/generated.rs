@@ -19,6 +19,11 @@ pub mod queries {
             pub name: T1,
             pub hair_color: Option<T2>,
         }
+        #[derive(Debug, Clone)]
+        pub struct InsertUserParamsOwned {
+            pub name: String,
+            pub hair_color: Option<String>,
+        }
         #[derive(Debug, Clone, PartialEq)]
         pub struct User {
             pub id: i32,
@@ -45,6 +50,23 @@ pub mod queries {
                 }
             }
         }
+        impl<'a> cornucopia_async::FromRowBorrowed<'a> for UserBorrowed<'a> {
+            fn from_row_borrowed(
+                row: &'a tokio_postgres::Row,
+            ) -> Result<Self, tokio_postgres::Error> {
+                Ok(Self {
+                    id: row.try_get("id")?,
+                    name: row.try_get("name")?,
+                    hair_color: row.try_get("hair_color")?,
+                })
+            }
+        }
+        impl cornucopia_async::FromRow for User {
+            fn from_row(row: &tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+                <UserBorrowed as cornucopia_async::FromRowBorrowed>::from_row_borrowed(row)
+                    .map(Into::into)
+            }
+        }
         #[derive(Debug, Clone, PartialEq)]
         pub struct Post {
             pub id: i32,
@@ -75,6 +97,24 @@ pub mod queries {
                 }
             }
         }
+        impl<'a> cornucopia_async::FromRowBorrowed<'a> for PostBorrowed<'a> {
+            fn from_row_borrowed(
+                row: &'a tokio_postgres::Row,
+            ) -> Result<Self, tokio_postgres::Error> {
+                Ok(Self {
+                    id: row.try_get("id")?,
+                    user_id: row.try_get("user_id")?,
+                    title: row.try_get("title")?,
+                    body: row.try_get("body")?,
+                })
+            }
+        }
+        impl cornucopia_async::FromRow for Post {
+            fn from_row(row: &tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+                <PostBorrowed as cornucopia_async::FromRowBorrowed>::from_row_borrowed(row)
+                    .map(Into::into)
+            }
+        }
         #[derive(Debug, Clone, PartialEq)]
         pub struct Comment {
             pub id: i32,
@@ -95,6 +135,23 @@ pub mod queries {
                 }
             }
         }
+        impl<'a> cornucopia_async::FromRowBorrowed<'a> for CommentBorrowed<'a> {
+            fn from_row_borrowed(
+                row: &'a tokio_postgres::Row,
+            ) -> Result<Self, tokio_postgres::Error> {
+                Ok(Self {
+                    id: row.try_get("id")?,
+                    post_id: row.try_get("post_id")?,
+                    text: row.try_get("text")?,
+                })
+            }
+        }
+        impl cornucopia_async::FromRow for Comment {
+            fn from_row(row: &tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+                <CommentBorrowed as cornucopia_async::FromRowBorrowed>::from_row_borrowed(row)
+                    .map(Into::into)
+            }
+        }
         #[derive(Debug, Clone, PartialEq)]
         pub struct SelectComplex {
             pub myuser_id: i32,
@@ -137,13 +194,34 @@ pub mod queries {
                 }
             }
         }
+        impl<'a> cornucopia_async::FromRowBorrowed<'a> for SelectComplexBorrowed<'a> {
+            fn from_row_borrowed(
+                row: &'a tokio_postgres::Row,
+            ) -> Result<Self, tokio_postgres::Error> {
+                Ok(Self {
+                    myuser_id: row.try_get("myuser_id")?,
+                    name: row.try_get("name")?,
+                    hair_color: row.try_get("hair_color")?,
+                    post_id: row.try_get("post_id")?,
+                    user_id: row.try_get("user_id")?,
+                    title: row.try_get("title")?,
+                    body: row.try_get("body")?,
+                })
+            }
+        }
+        impl cornucopia_async::FromRow for SelectComplex {
+            fn from_row(row: &tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+                <SelectComplexBorrowed as cornucopia_async::FromRowBorrowed>::from_row_borrowed(row)
+                    .map(Into::into)
+            }
+        }
         pub mod sync {
             use postgres::{fallible_iterator::FallibleIterator, GenericClient};
             pub struct UserQuery<'a, C: GenericClient, T, const N: usize> {
                 client: &'a mut C,
                 params: [&'a (dyn postgres_types::ToSql + Sync); N],
                 stmt: &'a mut cornucopia_sync::private::Stmt,
-                extractor: fn(&postgres::Row) -> super::UserBorrowed,
+                extractor: fn(&postgres::Row) -> Result<super::UserBorrowed, postgres::Error>,
                 mapper: fn(super::UserBorrowed) -> T,
             }
             impl<'a, C, T: 'a, const N: usize> UserQuery<'a, C, T, N>
@@ -162,39 +240,63 @@ pub mod queries {
                         mapper,
                     }
                 }
+                pub fn map_with<R, F: FnMut(super::UserBorrowed) -> R>(
+                    self,
+                    mut mapper: F,
+                ) -> cornucopia_sync::MappedQuery<
+                    'a,
+                    C,
+                    impl FnMut(&postgres::Row) -> Result<R, postgres::Error>,
+                    N,
+                > {
+                    self.try_map(move |it| Ok(mapper(it)))
+                }
+                pub fn try_map<
+                    R,
+                    E: From<postgres::Error>,
+                    F: FnMut(super::UserBorrowed) -> Result<R, E>,
+                >(
+                    self,
+                    mut mapper: F,
+                ) -> cornucopia_sync::MappedQuery<
+                    'a,
+                    C,
+                    impl FnMut(&postgres::Row) -> Result<R, E>,
+                    N,
+                > {
+                    let extractor = self.extractor;
+                    cornucopia_sync::MappedQuery::new(
+                        self.client,
+                        self.params,
+                        self.stmt,
+                        move |row| mapper(extractor(row)?),
+                    )
+                }
                 pub fn one(self) -> Result<T, postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client)?;
-                    let row = self.client.query_one(stmt, &self.params)?;
-                    Ok((self.mapper)((self.extractor)(&row)))
+                    let mapper = self.mapper;
+                    self.map_with(mapper).one()
                 }
                 pub fn all(self) -> Result<Vec<T>, postgres::Error> {
-                    self.iter()?.collect()
+                    let mapper = self.mapper;
+                    self.map_with(mapper).all()
                 }
                 pub fn opt(self) -> Result<Option<T>, postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client)?;
-                    Ok(self
-                        .client
-                        .query_opt(stmt, &self.params)?
-                        .map(|row| (self.mapper)((self.extractor)(&row))))
+                    let mapper = self.mapper;
+                    self.map_with(mapper).opt()
                 }
                 pub fn iter(
                     self,
                 ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
                 {
-                    let stmt = self.stmt.prepare(self.client)?;
-                    let it = self
-                        .client
-                        .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
-                        .iterator()
-                        .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
-                    Ok(it)
+                    let mapper = self.mapper;
+                    self.map_with(mapper).iter()
                 }
             }
             pub struct PostQuery<'a, C: GenericClient, T, const N: usize> {
                 client: &'a mut C,
                 params: [&'a (dyn postgres_types::ToSql + Sync); N],
                 stmt: &'a mut cornucopia_sync::private::Stmt,
-                extractor: fn(&postgres::Row) -> super::PostBorrowed,
+                extractor: fn(&postgres::Row) -> Result<super::PostBorrowed, postgres::Error>,
                 mapper: fn(super::PostBorrowed) -> T,
             }
             impl<'a, C, T: 'a, const N: usize> PostQuery<'a, C, T, N>
@@ -213,39 +315,63 @@ pub mod queries {
                         mapper,
                     }
                 }
+                pub fn map_with<R, F: FnMut(super::PostBorrowed) -> R>(
+                    self,
+                    mut mapper: F,
+                ) -> cornucopia_sync::MappedQuery<
+                    'a,
+                    C,
+                    impl FnMut(&postgres::Row) -> Result<R, postgres::Error>,
+                    N,
+                > {
+                    self.try_map(move |it| Ok(mapper(it)))
+                }
+                pub fn try_map<
+                    R,
+                    E: From<postgres::Error>,
+                    F: FnMut(super::PostBorrowed) -> Result<R, E>,
+                >(
+                    self,
+                    mut mapper: F,
+                ) -> cornucopia_sync::MappedQuery<
+                    'a,
+                    C,
+                    impl FnMut(&postgres::Row) -> Result<R, E>,
+                    N,
+                > {
+                    let extractor = self.extractor;
+                    cornucopia_sync::MappedQuery::new(
+                        self.client,
+                        self.params,
+                        self.stmt,
+                        move |row| mapper(extractor(row)?),
+                    )
+                }
                 pub fn one(self) -> Result<T, postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client)?;
-                    let row = self.client.query_one(stmt, &self.params)?;
-                    Ok((self.mapper)((self.extractor)(&row)))
+                    let mapper = self.mapper;
+                    self.map_with(mapper).one()
                 }
                 pub fn all(self) -> Result<Vec<T>, postgres::Error> {
-                    self.iter()?.collect()
+                    let mapper = self.mapper;
+                    self.map_with(mapper).all()
                 }
                 pub fn opt(self) -> Result<Option<T>, postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client)?;
-                    Ok(self
-                        .client
-                        .query_opt(stmt, &self.params)?
-                        .map(|row| (self.mapper)((self.extractor)(&row))))
+                    let mapper = self.mapper;
+                    self.map_with(mapper).opt()
                 }
                 pub fn iter(
                     self,
                 ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
                 {
-                    let stmt = self.stmt.prepare(self.client)?;
-                    let it = self
-                        .client
-                        .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
-                        .iterator()
-                        .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
-                    Ok(it)
+                    let mapper = self.mapper;
+                    self.map_with(mapper).iter()
                 }
             }
             pub struct CommentQuery<'a, C: GenericClient, T, const N: usize> {
                 client: &'a mut C,
                 params: [&'a (dyn postgres_types::ToSql + Sync); N],
                 stmt: &'a mut cornucopia_sync::private::Stmt,
-                extractor: fn(&postgres::Row) -> super::CommentBorrowed,
+                extractor: fn(&postgres::Row) -> Result<super::CommentBorrowed, postgres::Error>,
                 mapper: fn(super::CommentBorrowed) -> T,
             }
             impl<'a, C, T: 'a, const N: usize> CommentQuery<'a, C, T, N>
@@ -264,39 +390,64 @@ pub mod queries {
                         mapper,
                     }
                 }
+                pub fn map_with<R, F: FnMut(super::CommentBorrowed) -> R>(
+                    self,
+                    mut mapper: F,
+                ) -> cornucopia_sync::MappedQuery<
+                    'a,
+                    C,
+                    impl FnMut(&postgres::Row) -> Result<R, postgres::Error>,
+                    N,
+                > {
+                    self.try_map(move |it| Ok(mapper(it)))
+                }
+                pub fn try_map<
+                    R,
+                    E: From<postgres::Error>,
+                    F: FnMut(super::CommentBorrowed) -> Result<R, E>,
+                >(
+                    self,
+                    mut mapper: F,
+                ) -> cornucopia_sync::MappedQuery<
+                    'a,
+                    C,
+                    impl FnMut(&postgres::Row) -> Result<R, E>,
+                    N,
+                > {
+                    let extractor = self.extractor;
+                    cornucopia_sync::MappedQuery::new(
+                        self.client,
+                        self.params,
+                        self.stmt,
+                        move |row| mapper(extractor(row)?),
+                    )
+                }
                 pub fn one(self) -> Result<T, postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client)?;
-                    let row = self.client.query_one(stmt, &self.params)?;
-                    Ok((self.mapper)((self.extractor)(&row)))
+                    let mapper = self.mapper;
+                    self.map_with(mapper).one()
                 }
                 pub fn all(self) -> Result<Vec<T>, postgres::Error> {
-                    self.iter()?.collect()
+                    let mapper = self.mapper;
+                    self.map_with(mapper).all()
                 }
                 pub fn opt(self) -> Result<Option<T>, postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client)?;
-                    Ok(self
-                        .client
-                        .query_opt(stmt, &self.params)?
-                        .map(|row| (self.mapper)((self.extractor)(&row))))
+                    let mapper = self.mapper;
+                    self.map_with(mapper).opt()
                 }
                 pub fn iter(
                     self,
                 ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
                 {
-                    let stmt = self.stmt.prepare(self.client)?;
-                    let it = self
-                        .client
-                        .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
-                        .iterator()
-                        .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
-                    Ok(it)
+                    let mapper = self.mapper;
+                    self.map_with(mapper).iter()
                 }
             }
             pub struct SelectComplexQuery<'a, C: GenericClient, T, const N: usize> {
                 client: &'a mut C,
                 params: [&'a (dyn postgres_types::ToSql + Sync); N],
                 stmt: &'a mut cornucopia_sync::private::Stmt,
-                extractor: fn(&postgres::Row) -> super::SelectComplexBorrowed,
+                extractor:
+                    fn(&postgres::Row) -> Result<super::SelectComplexBorrowed, postgres::Error>,
                 mapper: fn(super::SelectComplexBorrowed) -> T,
             }
             impl<'a, C, T: 'a, const N: usize> SelectComplexQuery<'a, C, T, N>
@@ -315,36 +466,63 @@ pub mod queries {
                         mapper,
                     }
                 }
+                pub fn map_with<R, F: FnMut(super::SelectComplexBorrowed) -> R>(
+                    self,
+                    mut mapper: F,
+                ) -> cornucopia_sync::MappedQuery<
+                    'a,
+                    C,
+                    impl FnMut(&postgres::Row) -> Result<R, postgres::Error>,
+                    N,
+                > {
+                    self.try_map(move |it| Ok(mapper(it)))
+                }
+                pub fn try_map<
+                    R,
+                    E: From<postgres::Error>,
+                    F: FnMut(super::SelectComplexBorrowed) -> Result<R, E>,
+                >(
+                    self,
+                    mut mapper: F,
+                ) -> cornucopia_sync::MappedQuery<
+                    'a,
+                    C,
+                    impl FnMut(&postgres::Row) -> Result<R, E>,
+                    N,
+                > {
+                    let extractor = self.extractor;
+                    cornucopia_sync::MappedQuery::new(
+                        self.client,
+                        self.params,
+                        self.stmt,
+                        move |row| mapper(extractor(row)?),
+                    )
+                }
                 pub fn one(self) -> Result<T, postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client)?;
-                    let row = self.client.query_one(stmt, &self.params)?;
-                    Ok((self.mapper)((self.extractor)(&row)))
+                    let mapper = self.mapper;
+                    self.map_with(mapper).one()
                 }
                 pub fn all(self) -> Result<Vec<T>, postgres::Error> {
-                    self.iter()?.collect()
+                    let mapper = self.mapper;
+                    self.map_with(mapper).all()
                 }
                 pub fn opt(self) -> Result<Option<T>, postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client)?;
-                    Ok(self
-                        .client
-                        .query_opt(stmt, &self.params)?
-                        .map(|row| (self.mapper)((self.extractor)(&row))))
+                    let mapper = self.mapper;
+                    self.map_with(mapper).opt()
                 }
                 pub fn iter(
                     self,
                 ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
                 {
-                    let stmt = self.stmt.prepare(self.client)?;
-                    let it = self
-                        .client
-                        .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
-                        .iterator()
-                        .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
-                    Ok(it)
+                    let mapper = self.mapper;
+                    self.map_with(mapper).iter()
                 }
             }
             pub fn users() -> UsersStmt {
-                UsersStmt(cornucopia_sync::private::Stmt::new("SELECT * FROM users"))
+                UsersStmt(cornucopia_sync::private::Stmt::named(
+                    "SELECT * FROM users",
+                    "bench.users",
+                ))
             }
             pub struct UsersStmt(cornucopia_sync::private::Stmt);
             impl UsersStmt {
@@ -356,18 +534,18 @@ pub mod queries {
                         client,
                         params: [],
                         stmt: &mut self.0,
-                        extractor: |row| super::UserBorrowed {
-                            id: row.get(0),
-                            name: row.get(1),
-                            hair_color: row.get(2),
+                        extractor: |row| {
+                            < super::UserBorrowed as cornucopia_sync :: FromRowBorrowed > ::
+from_row_borrowed(row)
                         },
                         mapper: |it| <super::User>::from(it),
                     }
                 }
             }
             pub fn insert_user() -> InsertUserStmt {
-                InsertUserStmt(cornucopia_sync::private::Stmt::new(
+                InsertUserStmt(cornucopia_sync::private::Stmt::named(
                     "INSERT INTO users (name, hair_color) VALUES ($1, $2)",
+                    "bench.insert_user",
                 ))
             }
             pub struct InsertUserStmt(cornucopia_sync::private::Stmt);
@@ -383,8 +561,7 @@ pub mod queries {
                     name: &'a T1,
                     hair_color: &'a Option<T2>,
                 ) -> Result<u64, postgres::Error> {
-                    let stmt = self.0.prepare(client)?;
-                    client.execute(stmt, &[name, hair_color])
+                    cornucopia_sync::private::execute(client, &mut self.0, &[name, hair_color])
                 }
             }
             impl<
@@ -408,8 +585,31 @@ pub mod queries {
                     self.bind(client, &params.name, &params.hair_color)
                 }
             }
+            impl<'a, C: GenericClient>
+                cornucopia_sync::Params<
+                    'a,
+                    super::InsertUserParamsOwned,
+                    Result<u64, postgres::Error>,
+                    C,
+                > for InsertUserStmt
+            {
+                fn params(
+                    &'a mut self,
+                    client: &'a mut C,
+                    params: &'a super::InsertUserParamsOwned,
+                ) -> Result<u64, postgres::Error> {
+                    cornucopia_sync::private::execute(
+                        client,
+                        &mut self.0,
+                        &[&params.name, &params.hair_color],
+                    )
+                }
+            }
             pub fn posts() -> PostsStmt {
-                PostsStmt(cornucopia_sync::private::Stmt::new("SELECT * FROM posts"))
+                PostsStmt(cornucopia_sync::private::Stmt::named(
+                    "SELECT * FROM posts",
+                    "bench.posts",
+                ))
             }
             pub struct PostsStmt(cornucopia_sync::private::Stmt);
             impl PostsStmt {
@@ -421,19 +621,18 @@ pub mod queries {
                         client,
                         params: [],
                         stmt: &mut self.0,
-                        extractor: |row| super::PostBorrowed {
-                            id: row.get(0),
-                            user_id: row.get(1),
-                            title: row.get(2),
-                            body: row.get(3),
+                        extractor: |row| {
+                            < super::PostBorrowed as cornucopia_sync :: FromRowBorrowed > ::
+from_row_borrowed(row)
                         },
                         mapper: |it| <super::Post>::from(it),
                     }
                 }
             }
             pub fn post_by_user_ids() -> PostByUserIdsStmt {
-                PostByUserIdsStmt(cornucopia_sync::private::Stmt::new(
+                PostByUserIdsStmt(cornucopia_sync::private::Stmt::named(
                     "SELECT * FROM posts WHERE user_id = ANY($1)",
+                    "bench.post_by_user_ids",
                 ))
             }
             pub struct PostByUserIdsStmt(cornucopia_sync::private::Stmt);
@@ -447,19 +646,18 @@ pub mod queries {
                         client,
                         params: [ids],
                         stmt: &mut self.0,
-                        extractor: |row| super::PostBorrowed {
-                            id: row.get(0),
-                            user_id: row.get(1),
-                            title: row.get(2),
-                            body: row.get(3),
+                        extractor: |row| {
+                            < super::PostBorrowed as cornucopia_sync :: FromRowBorrowed > ::
+from_row_borrowed(row)
                         },
                         mapper: |it| <super::Post>::from(it),
                     }
                 }
             }
             pub fn comments() -> CommentsStmt {
-                CommentsStmt(cornucopia_sync::private::Stmt::new(
+                CommentsStmt(cornucopia_sync::private::Stmt::named(
                     "SELECT * FROM comments",
+                    "bench.comments",
                 ))
             }
             pub struct CommentsStmt(cornucopia_sync::private::Stmt);
@@ -472,18 +670,18 @@ pub mod queries {
                         client,
                         params: [],
                         stmt: &mut self.0,
-                        extractor: |row| super::CommentBorrowed {
-                            id: row.get(0),
-                            post_id: row.get(1),
-                            text: row.get(2),
+                        extractor: |row| {
+                            < super::CommentBorrowed as cornucopia_sync :: FromRowBorrowed > ::
+from_row_borrowed(row)
                         },
                         mapper: |it| <super::Comment>::from(it),
                     }
                 }
             }
             pub fn comments_by_post_id() -> CommentsByPostIdStmt {
-                CommentsByPostIdStmt(cornucopia_sync::private::Stmt::new(
+                CommentsByPostIdStmt(cornucopia_sync::private::Stmt::named(
                     "SELECT * FROM comments WHERE post_id = ANY($1)",
+                    "bench.comments_by_post_id",
                 ))
             }
             pub struct CommentsByPostIdStmt(cornucopia_sync::private::Stmt);
@@ -497,17 +695,16 @@ pub mod queries {
                         client,
                         params: [ids],
                         stmt: &mut self.0,
-                        extractor: |row| super::CommentBorrowed {
-                            id: row.get(0),
-                            post_id: row.get(1),
-                            text: row.get(2),
+                        extractor: |row| {
+                            < super::CommentBorrowed as cornucopia_sync :: FromRowBorrowed > ::
+from_row_borrowed(row)
                         },
                         mapper: |it| <super::Comment>::from(it),
                     }
                 }
             }
             pub fn select_complex() -> SelectComplexStmt {
-                SelectComplexStmt(cornucopia_sync :: private :: Stmt :: new("SELECT u.id as myuser_id, u.name, u.hair_color, p.id as post_id, p.user_id, p.title, p.body FROM users as u LEFT JOIN posts as p on u.id = p.user_id"))
+                SelectComplexStmt(cornucopia_sync :: private :: Stmt :: named("SELECT u.id as myuser_id, u.name, u.hair_color, p.id as post_id, p.user_id, p.title, p.body FROM users as u LEFT JOIN posts as p on u.id = p.user_id", "bench.select_complex"))
             }
             pub struct SelectComplexStmt(cornucopia_sync::private::Stmt);
             impl SelectComplexStmt {
@@ -519,14 +716,9 @@ pub mod queries {
                         client,
                         params: [],
                         stmt: &mut self.0,
-                        extractor: |row| super::SelectComplexBorrowed {
-                            myuser_id: row.get(0),
-                            name: row.get(1),
-                            hair_color: row.get(2),
-                            post_id: row.get(3),
-                            user_id: row.get(4),
-                            title: row.get(5),
-                            body: row.get(6),
+                        extractor: |row| {
+                            < super::SelectComplexBorrowed as cornucopia_sync :: FromRowBorrowed > ::
+from_row_borrowed(row)
                         },
                         mapper: |it| <super::SelectComplex>::from(it),
                     }
@@ -541,7 +733,8 @@ pub mod queries {
                 client: &'a C,
                 params: [&'a (dyn postgres_types::ToSql + Sync); N],
                 stmt: &'a mut cornucopia_async::private::Stmt,
-                extractor: fn(&tokio_postgres::Row) -> super::UserBorrowed,
+                extractor:
+                    fn(&tokio_postgres::Row) -> Result<super::UserBorrowed, tokio_postgres::Error>,
                 mapper: fn(super::UserBorrowed) -> T,
             }
             impl<'a, C, T: 'a, const N: usize> UserQuery<'a, C, T, N>
@@ -560,21 +753,49 @@ pub mod queries {
                         mapper,
                     }
                 }
+                pub fn map_with<R, F: FnMut(super::UserBorrowed) -> R>(
+                    self,
+                    mut mapper: F,
+                ) -> cornucopia_async::MappedQuery<
+                    'a,
+                    C,
+                    impl FnMut(&tokio_postgres::Row) -> Result<R, tokio_postgres::Error>,
+                    N,
+                > {
+                    self.try_map(move |it| Ok(mapper(it)))
+                }
+                pub fn try_map<
+                    R,
+                    E: From<tokio_postgres::Error>,
+                    F: FnMut(super::UserBorrowed) -> Result<R, E>,
+                >(
+                    self,
+                    mut mapper: F,
+                ) -> cornucopia_async::MappedQuery<
+                    'a,
+                    C,
+                    impl FnMut(&tokio_postgres::Row) -> Result<R, E>,
+                    N,
+                > {
+                    let extractor = self.extractor;
+                    cornucopia_async::MappedQuery::new(
+                        self.client,
+                        self.params,
+                        self.stmt,
+                        move |row| mapper(extractor(row)?),
+                    )
+                }
                 pub async fn one(self) -> Result<T, tokio_postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client).await?;
-                    let row = self.client.query_one(stmt, &self.params).await?;
-                    Ok((self.mapper)((self.extractor)(&row)))
+                    let mapper = self.mapper;
+                    self.map_with(mapper).one().await
                 }
                 pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error> {
-                    self.iter().await?.try_collect().await
+                    let mapper = self.mapper;
+                    self.map_with(mapper).all().await
                 }
                 pub async fn opt(self) -> Result<Option<T>, tokio_postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client).await?;
-                    Ok(self
-                        .client
-                        .query_opt(stmt, &self.params)
-                        .await?
-                        .map(|row| (self.mapper)((self.extractor)(&row))))
+                    let mapper = self.mapper;
+                    self.map_with(mapper).opt().await
                 }
                 pub async fn iter(
                     self,
@@ -582,21 +803,16 @@ pub mod queries {
                     impl futures::Stream<Item = Result<T, tokio_postgres::Error>> + 'a,
                     tokio_postgres::Error,
                 > {
-                    let stmt = self.stmt.prepare(self.client).await?;
-                    let it = self
-                        .client
-                        .query_raw(stmt, cornucopia_async::private::slice_iter(&self.params))
-                        .await?
-                        .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))))
-                        .into_stream();
-                    Ok(it)
+                    let mapper = self.mapper;
+                    self.map_with(mapper).iter().await
                 }
             }
             pub struct PostQuery<'a, C: GenericClient, T, const N: usize> {
                 client: &'a C,
                 params: [&'a (dyn postgres_types::ToSql + Sync); N],
                 stmt: &'a mut cornucopia_async::private::Stmt,
-                extractor: fn(&tokio_postgres::Row) -> super::PostBorrowed,
+                extractor:
+                    fn(&tokio_postgres::Row) -> Result<super::PostBorrowed, tokio_postgres::Error>,
                 mapper: fn(super::PostBorrowed) -> T,
             }
             impl<'a, C, T: 'a, const N: usize> PostQuery<'a, C, T, N>
@@ -615,21 +831,49 @@ pub mod queries {
                         mapper,
                     }
                 }
+                pub fn map_with<R, F: FnMut(super::PostBorrowed) -> R>(
+                    self,
+                    mut mapper: F,
+                ) -> cornucopia_async::MappedQuery<
+                    'a,
+                    C,
+                    impl FnMut(&tokio_postgres::Row) -> Result<R, tokio_postgres::Error>,
+                    N,
+                > {
+                    self.try_map(move |it| Ok(mapper(it)))
+                }
+                pub fn try_map<
+                    R,
+                    E: From<tokio_postgres::Error>,
+                    F: FnMut(super::PostBorrowed) -> Result<R, E>,
+                >(
+                    self,
+                    mut mapper: F,
+                ) -> cornucopia_async::MappedQuery<
+                    'a,
+                    C,
+                    impl FnMut(&tokio_postgres::Row) -> Result<R, E>,
+                    N,
+                > {
+                    let extractor = self.extractor;
+                    cornucopia_async::MappedQuery::new(
+                        self.client,
+                        self.params,
+                        self.stmt,
+                        move |row| mapper(extractor(row)?),
+                    )
+                }
                 pub async fn one(self) -> Result<T, tokio_postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client).await?;
-                    let row = self.client.query_one(stmt, &self.params).await?;
-                    Ok((self.mapper)((self.extractor)(&row)))
+                    let mapper = self.mapper;
+                    self.map_with(mapper).one().await
                 }
                 pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error> {
-                    self.iter().await?.try_collect().await
+                    let mapper = self.mapper;
+                    self.map_with(mapper).all().await
                 }
                 pub async fn opt(self) -> Result<Option<T>, tokio_postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client).await?;
-                    Ok(self
-                        .client
-                        .query_opt(stmt, &self.params)
-                        .await?
-                        .map(|row| (self.mapper)((self.extractor)(&row))))
+                    let mapper = self.mapper;
+                    self.map_with(mapper).opt().await
                 }
                 pub async fn iter(
                     self,
@@ -637,21 +881,18 @@ pub mod queries {
                     impl futures::Stream<Item = Result<T, tokio_postgres::Error>> + 'a,
                     tokio_postgres::Error,
                 > {
-                    let stmt = self.stmt.prepare(self.client).await?;
-                    let it = self
-                        .client
-                        .query_raw(stmt, cornucopia_async::private::slice_iter(&self.params))
-                        .await?
-                        .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))))
-                        .into_stream();
-                    Ok(it)
+                    let mapper = self.mapper;
+                    self.map_with(mapper).iter().await
                 }
             }
             pub struct CommentQuery<'a, C: GenericClient, T, const N: usize> {
                 client: &'a C,
                 params: [&'a (dyn postgres_types::ToSql + Sync); N],
                 stmt: &'a mut cornucopia_async::private::Stmt,
-                extractor: fn(&tokio_postgres::Row) -> super::CommentBorrowed,
+                extractor: fn(
+                    &tokio_postgres::Row,
+                )
+                    -> Result<super::CommentBorrowed, tokio_postgres::Error>,
                 mapper: fn(super::CommentBorrowed) -> T,
             }
             impl<'a, C, T: 'a, const N: usize> CommentQuery<'a, C, T, N>
@@ -670,21 +911,49 @@ pub mod queries {
                         mapper,
                     }
                 }
+                pub fn map_with<R, F: FnMut(super::CommentBorrowed) -> R>(
+                    self,
+                    mut mapper: F,
+                ) -> cornucopia_async::MappedQuery<
+                    'a,
+                    C,
+                    impl FnMut(&tokio_postgres::Row) -> Result<R, tokio_postgres::Error>,
+                    N,
+                > {
+                    self.try_map(move |it| Ok(mapper(it)))
+                }
+                pub fn try_map<
+                    R,
+                    E: From<tokio_postgres::Error>,
+                    F: FnMut(super::CommentBorrowed) -> Result<R, E>,
+                >(
+                    self,
+                    mut mapper: F,
+                ) -> cornucopia_async::MappedQuery<
+                    'a,
+                    C,
+                    impl FnMut(&tokio_postgres::Row) -> Result<R, E>,
+                    N,
+                > {
+                    let extractor = self.extractor;
+                    cornucopia_async::MappedQuery::new(
+                        self.client,
+                        self.params,
+                        self.stmt,
+                        move |row| mapper(extractor(row)?),
+                    )
+                }
                 pub async fn one(self) -> Result<T, tokio_postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client).await?;
-                    let row = self.client.query_one(stmt, &self.params).await?;
-                    Ok((self.mapper)((self.extractor)(&row)))
+                    let mapper = self.mapper;
+                    self.map_with(mapper).one().await
                 }
                 pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error> {
-                    self.iter().await?.try_collect().await
+                    let mapper = self.mapper;
+                    self.map_with(mapper).all().await
                 }
                 pub async fn opt(self) -> Result<Option<T>, tokio_postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client).await?;
-                    Ok(self
-                        .client
-                        .query_opt(stmt, &self.params)
-                        .await?
-                        .map(|row| (self.mapper)((self.extractor)(&row))))
+                    let mapper = self.mapper;
+                    self.map_with(mapper).opt().await
                 }
                 pub async fn iter(
                     self,
@@ -692,21 +961,18 @@ pub mod queries {
                     impl futures::Stream<Item = Result<T, tokio_postgres::Error>> + 'a,
                     tokio_postgres::Error,
                 > {
-                    let stmt = self.stmt.prepare(self.client).await?;
-                    let it = self
-                        .client
-                        .query_raw(stmt, cornucopia_async::private::slice_iter(&self.params))
-                        .await?
-                        .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))))
-                        .into_stream();
-                    Ok(it)
+                    let mapper = self.mapper;
+                    self.map_with(mapper).iter().await
                 }
             }
             pub struct SelectComplexQuery<'a, C: GenericClient, T, const N: usize> {
                 client: &'a C,
                 params: [&'a (dyn postgres_types::ToSql + Sync); N],
                 stmt: &'a mut cornucopia_async::private::Stmt,
-                extractor: fn(&tokio_postgres::Row) -> super::SelectComplexBorrowed,
+                extractor: fn(
+                    &tokio_postgres::Row,
+                )
+                    -> Result<super::SelectComplexBorrowed, tokio_postgres::Error>,
                 mapper: fn(super::SelectComplexBorrowed) -> T,
             }
             impl<'a, C, T: 'a, const N: usize> SelectComplexQuery<'a, C, T, N>
@@ -725,21 +991,49 @@ pub mod queries {
                         mapper,
                     }
                 }
+                pub fn map_with<R, F: FnMut(super::SelectComplexBorrowed) -> R>(
+                    self,
+                    mut mapper: F,
+                ) -> cornucopia_async::MappedQuery<
+                    'a,
+                    C,
+                    impl FnMut(&tokio_postgres::Row) -> Result<R, tokio_postgres::Error>,
+                    N,
+                > {
+                    self.try_map(move |it| Ok(mapper(it)))
+                }
+                pub fn try_map<
+                    R,
+                    E: From<tokio_postgres::Error>,
+                    F: FnMut(super::SelectComplexBorrowed) -> Result<R, E>,
+                >(
+                    self,
+                    mut mapper: F,
+                ) -> cornucopia_async::MappedQuery<
+                    'a,
+                    C,
+                    impl FnMut(&tokio_postgres::Row) -> Result<R, E>,
+                    N,
+                > {
+                    let extractor = self.extractor;
+                    cornucopia_async::MappedQuery::new(
+                        self.client,
+                        self.params,
+                        self.stmt,
+                        move |row| mapper(extractor(row)?),
+                    )
+                }
                 pub async fn one(self) -> Result<T, tokio_postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client).await?;
-                    let row = self.client.query_one(stmt, &self.params).await?;
-                    Ok((self.mapper)((self.extractor)(&row)))
+                    let mapper = self.mapper;
+                    self.map_with(mapper).one().await
                 }
                 pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error> {
-                    self.iter().await?.try_collect().await
+                    let mapper = self.mapper;
+                    self.map_with(mapper).all().await
                 }
                 pub async fn opt(self) -> Result<Option<T>, tokio_postgres::Error> {
-                    let stmt = self.stmt.prepare(self.client).await?;
-                    Ok(self
-                        .client
-                        .query_opt(stmt, &self.params)
-                        .await?
-                        .map(|row| (self.mapper)((self.extractor)(&row))))
+                    let mapper = self.mapper;
+                    self.map_with(mapper).opt().await
                 }
                 pub async fn iter(
                     self,
@@ -747,18 +1041,15 @@ pub mod queries {
                     impl futures::Stream<Item = Result<T, tokio_postgres::Error>> + 'a,
                     tokio_postgres::Error,
                 > {
-                    let stmt = self.stmt.prepare(self.client).await?;
-                    let it = self
-                        .client
-                        .query_raw(stmt, cornucopia_async::private::slice_iter(&self.params))
-                        .await?
-                        .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))))
-                        .into_stream();
-                    Ok(it)
+                    let mapper = self.mapper;
+                    self.map_with(mapper).iter().await
                 }
             }
             pub fn users() -> UsersStmt {
-                UsersStmt(cornucopia_async::private::Stmt::new("SELECT * FROM users"))
+                UsersStmt(cornucopia_async::private::Stmt::named(
+                    "SELECT * FROM users",
+                    "bench.users",
+                ))
             }
             pub struct UsersStmt(cornucopia_async::private::Stmt);
             impl UsersStmt {
@@ -770,18 +1061,18 @@ pub mod queries {
                         client,
                         params: [],
                         stmt: &mut self.0,
-                        extractor: |row| super::UserBorrowed {
-                            id: row.get(0),
-                            name: row.get(1),
-                            hair_color: row.get(2),
+                        extractor: |row| {
+                            < super::UserBorrowed as cornucopia_async :: FromRowBorrowed > ::
+from_row_borrowed(row)
                         },
                         mapper: |it| <super::User>::from(it),
                     }
                 }
             }
             pub fn insert_user() -> InsertUserStmt {
-                InsertUserStmt(cornucopia_async::private::Stmt::new(
+                InsertUserStmt(cornucopia_async::private::Stmt::named(
                     "INSERT INTO users (name, hair_color) VALUES ($1, $2)",
+                    "bench.insert_user",
                 ))
             }
             pub struct InsertUserStmt(cornucopia_async::private::Stmt);
@@ -797,8 +1088,8 @@ pub mod queries {
                     name: &'a T1,
                     hair_color: &'a Option<T2>,
                 ) -> Result<u64, tokio_postgres::Error> {
-                    let stmt = self.0.prepare(client).await?;
-                    client.execute(stmt, &[name, hair_color]).await
+                    cornucopia_async::private::execute(client, &mut self.0, &[name, hair_color])
+                        .await
                 }
             }
             impl<
@@ -834,8 +1125,46 @@ pub mod queries {
                     Box::pin(self.bind(client, &params.name, &params.hair_color))
                 }
             }
+            impl<'a, C: GenericClient + Send + Sync>
+                cornucopia_async::Params<
+                    'a,
+                    super::InsertUserParamsOwned,
+                    std::pin::Pin<
+                        Box<
+                            dyn futures::Future<Output = Result<u64, tokio_postgres::Error>>
+                                + Send
+                                + 'a,
+                        >,
+                    >,
+                    C,
+                > for InsertUserStmt
+            {
+                fn params(
+                    &'a mut self,
+                    client: &'a C,
+                    params: &'a super::InsertUserParamsOwned,
+                ) -> std::pin::Pin<
+                    Box<
+                        dyn futures::Future<Output = Result<u64, tokio_postgres::Error>>
+                            + Send
+                            + 'a,
+                    >,
+                > {
+                    Box::pin(async move {
+                        cornucopia_async::private::execute(
+                            client,
+                            &mut self.0,
+                            &[&params.name, &params.hair_color],
+                        )
+                        .await
+                    })
+                }
+            }
             pub fn posts() -> PostsStmt {
-                PostsStmt(cornucopia_async::private::Stmt::new("SELECT * FROM posts"))
+                PostsStmt(cornucopia_async::private::Stmt::named(
+                    "SELECT * FROM posts",
+                    "bench.posts",
+                ))
             }
             pub struct PostsStmt(cornucopia_async::private::Stmt);
             impl PostsStmt {
@@ -847,19 +1176,18 @@ pub mod queries {
                         client,
                         params: [],
                         stmt: &mut self.0,
-                        extractor: |row| super::PostBorrowed {
-                            id: row.get(0),
-                            user_id: row.get(1),
-                            title: row.get(2),
-                            body: row.get(3),
+                        extractor: |row| {
+                            < super::PostBorrowed as cornucopia_async :: FromRowBorrowed > ::
+from_row_borrowed(row)
                         },
                         mapper: |it| <super::Post>::from(it),
                     }
                 }
             }
             pub fn post_by_user_ids() -> PostByUserIdsStmt {
-                PostByUserIdsStmt(cornucopia_async::private::Stmt::new(
+                PostByUserIdsStmt(cornucopia_async::private::Stmt::named(
                     "SELECT * FROM posts WHERE user_id = ANY($1)",
+                    "bench.post_by_user_ids",
                 ))
             }
             pub struct PostByUserIdsStmt(cornucopia_async::private::Stmt);
@@ -873,19 +1201,18 @@ pub mod queries {
                         client,
                         params: [ids],
                         stmt: &mut self.0,
-                        extractor: |row| super::PostBorrowed {
-                            id: row.get(0),
-                            user_id: row.get(1),
-                            title: row.get(2),
-                            body: row.get(3),
+                        extractor: |row| {
+                            < super::PostBorrowed as cornucopia_async :: FromRowBorrowed > ::
+from_row_borrowed(row)
                         },
                         mapper: |it| <super::Post>::from(it),
                     }
                 }
             }
             pub fn comments() -> CommentsStmt {
-                CommentsStmt(cornucopia_async::private::Stmt::new(
+                CommentsStmt(cornucopia_async::private::Stmt::named(
                     "SELECT * FROM comments",
+                    "bench.comments",
                 ))
             }
             pub struct CommentsStmt(cornucopia_async::private::Stmt);
@@ -898,18 +1225,18 @@ pub mod queries {
                         client,
                         params: [],
                         stmt: &mut self.0,
-                        extractor: |row| super::CommentBorrowed {
-                            id: row.get(0),
-                            post_id: row.get(1),
-                            text: row.get(2),
+                        extractor: |row| {
+                            < super::CommentBorrowed as cornucopia_async :: FromRowBorrowed > ::
+from_row_borrowed(row)
                         },
                         mapper: |it| <super::Comment>::from(it),
                     }
                 }
             }
             pub fn comments_by_post_id() -> CommentsByPostIdStmt {
-                CommentsByPostIdStmt(cornucopia_async::private::Stmt::new(
+                CommentsByPostIdStmt(cornucopia_async::private::Stmt::named(
                     "SELECT * FROM comments WHERE post_id = ANY($1)",
+                    "bench.comments_by_post_id",
                 ))
             }
             pub struct CommentsByPostIdStmt(cornucopia_async::private::Stmt);
@@ -923,17 +1250,16 @@ pub mod queries {
                         client,
                         params: [ids],
                         stmt: &mut self.0,
-                        extractor: |row| super::CommentBorrowed {
-                            id: row.get(0),
-                            post_id: row.get(1),
-                            text: row.get(2),
+                        extractor: |row| {
+                            < super::CommentBorrowed as cornucopia_async :: FromRowBorrowed > ::
+from_row_borrowed(row)
                         },
                         mapper: |it| <super::Comment>::from(it),
                     }
                 }
             }
             pub fn select_complex() -> SelectComplexStmt {
-                SelectComplexStmt(cornucopia_async :: private :: Stmt :: new("SELECT u.id as myuser_id, u.name, u.hair_color, p.id as post_id, p.user_id, p.title, p.body FROM users as u LEFT JOIN posts as p on u.id = p.user_id"))
+                SelectComplexStmt(cornucopia_async :: private :: Stmt :: named("SELECT u.id as myuser_id, u.name, u.hair_color, p.id as post_id, p.user_id, p.title, p.body FROM users as u LEFT JOIN posts as p on u.id = p.user_id", "bench.select_complex"))
             }
             pub struct SelectComplexStmt(cornucopia_async::private::Stmt);
             impl SelectComplexStmt {
@@ -945,14 +1271,9 @@ pub mod queries {
                         client,
                         params: [],
                         stmt: &mut self.0,
-                        extractor: |row| super::SelectComplexBorrowed {
-                            myuser_id: row.get(0),
-                            name: row.get(1),
-                            hair_color: row.get(2),
-                            post_id: row.get(3),
-                            user_id: row.get(4),
-                            title: row.get(5),
-                            body: row.get(6),
+                        extractor: |row| {
+                            < super::SelectComplexBorrowed as cornucopia_async :: FromRowBorrowed > ::
+from_row_borrowed(row)
                         },
                         mapper: |it| <super::SelectComplex>::from(it),
                     }
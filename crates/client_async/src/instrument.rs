@@ -0,0 +1,77 @@
+use std::{future::Future, time::Instant};
+
+use cornucopia_client_core::{observer, QueryInfo, QueryObserver, QueryOutcome};
+use tokio_postgres::Error;
+
+use crate::private::Stmt;
+
+/// Span of a statement call, following OpenTelemetry database conventions,
+/// reported to the registered [`QueryObserver`].
+///
+/// The tracing span does nothing unless the `tracing` feature is enabled.
+pub(crate) struct QuerySpan {
+    info: QueryInfo,
+    observer: Option<&'static dyn QueryObserver>,
+    start: Instant,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+}
+
+impl QuerySpan {
+    pub(crate) fn new(stmt: &Stmt, method: &'static str, nb_params: usize) -> Self {
+        let info = QueryInfo {
+            // Unnamed statements are identified by their SQL text
+            name: stmt.name().unwrap_or(stmt.query()),
+            sql: stmt.query(),
+            method,
+            nb_params,
+        };
+        let observer = observer();
+        if let Some(observer) = observer {
+            observer.before(&info);
+        }
+        Self {
+            info,
+            observer,
+            start: Instant::now(),
+            #[cfg(feature = "tracing")]
+            span: tracing::info_span!(
+                "cornucopia.query",
+                otel.name = info.name,
+                otel.kind = "client",
+                db.system = "postgresql",
+                db.statement = info.sql,
+                db.operation = method,
+                db.response.returned_rows = tracing::field::Empty,
+            ),
+        }
+    }
+
+    pub(crate) async fn instrument<T>(&self, fut: impl Future<Output = T>) -> T {
+        #[cfg(feature = "tracing")]
+        return tracing::Instrument::instrument(fut, self.span.clone()).await;
+        #[cfg(not(feature = "tracing"))]
+        fut.await
+    }
+
+    /// Reports the database answer, with its number of returned or affected rows when known
+    pub(crate) fn finish<T>(
+        &self,
+        result: &Result<T, Error>,
+        rows: impl FnOnce(&T) -> Option<u64>,
+    ) {
+        let rows = result.as_ref().ok().and_then(rows);
+        #[cfg(feature = "tracing")]
+        if let Some(rows) = rows {
+            self.span.record("db.response.returned_rows", rows);
+        }
+        if let Some(observer) = self.observer {
+            let outcome = QueryOutcome {
+                rows,
+                duration: self.start.elapsed(),
+                error: result.as_ref().err(),
+            };
+            observer.after(&self.info, &outcome);
+        }
+    }
+}
@@ -2,14 +2,25 @@
 pub mod private;
 
 pub use crate::generic_client::GenericClient;
-pub use cornucopia_client_core::{ArrayIterator, ArraySql, BytesSql, IterSql, StringSql};
+pub use crate::mapped_query::MappedQuery;
+pub use crate::row_count::RowCountError;
+pub use cornucopia_client_core::{
+    set_observer, ArrayIterator, ArraySql, BytesSql, FromRow, FromRowBorrowed, IterSql, QueryInfo,
+    QueryObserver, QueryOutcome, StringSql,
+};
 
 #[cfg(feature = "with-serde_json-1")]
 pub use cornucopia_client_core::JsonSql;
 
+#[cfg(feature = "metrics")]
+pub use cornucopia_client_core::MetricsObserver;
+
 #[cfg(feature = "deadpool")]
 mod deadpool;
 mod generic_client;
+mod instrument;
+mod mapped_query;
+mod row_count;
 
 /// This trait allows you to bind parameters to a query using a single
 /// struct, rather than passing each bind parameter as a function parameter.
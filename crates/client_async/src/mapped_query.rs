@@ -0,0 +1,96 @@
+use futures::{Stream, StreamExt};
+use tokio_postgres::{types::ToSql, Error, Row};
+
+use crate::{
+    instrument::QuerySpan,
+    private::{slice_iter, Stmt},
+    GenericClient,
+};
+
+/// Query whose rows are mapped by a closure, which can capture its context
+/// and fail with an error merged into the query result.
+///
+/// Obtained from the `map_with` and `try_map` methods of generated queries.
+pub struct MappedQuery<'a, C: GenericClient, F, const N: usize> {
+    client: &'a C,
+    params: [&'a (dyn ToSql + Sync); N],
+    stmt: &'a mut Stmt,
+    mapper: F,
+}
+
+impl<'a, C, F, R, E, const N: usize> MappedQuery<'a, C, F, N>
+where
+    C: GenericClient,
+    F: FnMut(&Row) -> Result<R, E>,
+    E: From<Error>,
+{
+    #[doc(hidden)]
+    pub fn new(
+        client: &'a C,
+        params: [&'a (dyn ToSql + Sync); N],
+        stmt: &'a mut Stmt,
+        mapper: F,
+    ) -> Self {
+        Self {
+            client,
+            params,
+            stmt,
+            mapper,
+        }
+    }
+
+    pub async fn one(mut self) -> Result<R, E> {
+        let span = QuerySpan::new(self.stmt, "one", N);
+        let row = span
+            .instrument(async {
+                let stmt = self.stmt.prepare(self.client).await?;
+                self.client.query_one(stmt, &self.params).await
+            })
+            .await;
+        span.finish(&row, |_| Some(1));
+        (self.mapper)(&row?)
+    }
+
+    pub async fn all(mut self) -> Result<Vec<R>, E> {
+        let span = QuerySpan::new(self.stmt, "all", N);
+        let rows = span
+            .instrument(async {
+                let stmt = self.stmt.prepare(self.client).await?;
+                self.client.query(stmt, &self.params).await
+            })
+            .await;
+        span.finish(&rows, |rows| Some(rows.len() as u64));
+        rows?.iter().map(&mut self.mapper).collect()
+    }
+
+    pub async fn opt(mut self) -> Result<Option<R>, E> {
+        let span = QuerySpan::new(self.stmt, "opt", N);
+        let row = span
+            .instrument(async {
+                let stmt = self.stmt.prepare(self.client).await?;
+                self.client.query_opt(stmt, &self.params).await
+            })
+            .await;
+        span.finish(&row, |row| Some(row.is_some().into()));
+        row?.map(|row| (self.mapper)(&row)).transpose()
+    }
+
+    pub async fn iter(self) -> Result<impl Stream<Item = Result<R, E>> + 'a, E>
+    where
+        F: 'a,
+        R: 'a,
+        E: 'a,
+    {
+        let span = QuerySpan::new(self.stmt, "iter", N);
+        let rows = span
+            .instrument(async {
+                let stmt = self.stmt.prepare(self.client).await?;
+                self.client.query_raw(stmt, slice_iter(&self.params)).await
+            })
+            .await;
+        span.finish(&rows, |_| None);
+        let mut mapper = self.mapper;
+        let it = rows?.map(move |res| res.map_err(E::from).and_then(|row| mapper(&row)));
+        Ok(it)
+    }
+}
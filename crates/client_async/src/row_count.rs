@@ -0,0 +1,42 @@
+use std::fmt::Display;
+
+/// Error returned by execute queries declaring the exact number of rows
+/// they must affect with `!expect`.
+#[derive(Debug)]
+pub enum RowCountError {
+    /// The statement failed
+    Db(tokio_postgres::Error),
+    /// The statement did not affect the expected number of rows
+    Unexpected { expected: u64, actual: u64 },
+    /// Rolling back to a savepoint requires running the statement in a transaction
+    NoTransaction,
+}
+
+impl From<tokio_postgres::Error> for RowCountError {
+    fn from(e: tokio_postgres::Error) -> Self {
+        Self::Db(e)
+    }
+}
+
+impl Display for RowCountError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Db(e) => e.fmt(f),
+            Self::Unexpected { expected, actual } => {
+                write!(f, "expected {expected} affected rows, got {actual}")
+            }
+            Self::NoTransaction => {
+                f.write_str("rolling back to a savepoint requires a transaction")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RowCountError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Db(e) => Some(e),
+            Self::Unexpected { .. } | Self::NoTransaction => None,
+        }
+    }
+}
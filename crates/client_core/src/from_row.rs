@@ -0,0 +1,19 @@
+use tokio_postgres::{Error, Row};
+
+/// Decodes a struct owning its data from a row, matching its fields with the
+/// columns by name.
+pub trait FromRow: Sized {
+    fn from_row(row: &Row) -> Result<Self, Error>;
+}
+
+/// Decodes a struct that may borrow its data from a row, matching its fields
+/// with the columns by name.
+pub trait FromRowBorrowed<'a>: Sized {
+    fn from_row_borrowed(row: &'a Row) -> Result<Self, Error>;
+}
+
+impl<'a, T: FromRow> FromRowBorrowed<'a> for T {
+    fn from_row_borrowed(row: &'a Row) -> Result<Self, Error> {
+        T::from_row(row)
+    }
+}
@@ -1,13 +1,20 @@
 mod array_iterator;
 mod domain;
+mod from_row;
+mod observer;
 mod type_traits;
 mod utils;
 
 pub use array_iterator::ArrayIterator;
 pub use domain::{Domain, DomainArray};
+pub use from_row::{FromRow, FromRowBorrowed};
+pub use observer::{observer, set_observer, QueryInfo, QueryObserver, QueryOutcome};
 pub use type_traits::{ArraySql, BytesSql, IterSql, StringSql};
 
 #[cfg(feature = "with-serde_json-1")]
 pub use type_traits::JsonSql;
 
+#[cfg(feature = "metrics")]
+pub use observer::MetricsObserver;
+
 pub use utils::slice_iter;
@@ -0,0 +1,71 @@
+use std::{sync::OnceLock, time::Duration};
+
+use tokio_postgres::Error;
+
+/// Identity of an observed statement call
+#[derive(Debug, Clone, Copy)]
+pub struct QueryInfo {
+    /// Name of the statement, made of its module and query names (e.g. `module.query`), or its
+    /// SQL text when unnamed
+    pub name: &'static str,
+    /// SQL text of the statement
+    pub sql: &'static str,
+    /// Called method: `one`, `all`, `opt`, `iter`, `execute` or `prepare`
+    pub method: &'static str,
+    /// Number of bound parameters
+    pub nb_params: usize,
+}
+
+/// Outcome of an observed statement call
+#[derive(Debug)]
+pub struct QueryOutcome<'a> {
+    /// Number of returned or affected rows, unknown for streamed rows
+    pub rows: Option<u64>,
+    pub duration: Duration,
+    pub error: Option<&'a Error>,
+}
+
+/// Observes every statement call made by generated queries, to collect metrics for example.
+///
+/// Register one using [`set_observer`], calls are not observed otherwise.
+pub trait QueryObserver: Send + Sync {
+    /// Called before the statement is sent to the database
+    fn before(&self, _info: &QueryInfo) {}
+
+    /// Called once the database answered
+    fn after(&self, _info: &QueryInfo, _outcome: &QueryOutcome) {}
+}
+
+static OBSERVER: OnceLock<Box<dyn QueryObserver>> = OnceLock::new();
+
+/// Registers the observer of all statement calls.
+///
+/// Only one observer can be registered, the given one is returned back if one already is.
+pub fn set_observer<O: QueryObserver + 'static>(observer: O) -> Result<(), Box<dyn QueryObserver>> {
+    OBSERVER.set(Box::new(observer))
+}
+
+#[doc(hidden)]
+pub fn observer() -> Option<&'static dyn QueryObserver> {
+    OBSERVER.get().map(AsRef::as_ref)
+}
+
+/// Observer recording calls using the `metrics` crate.
+///
+/// Records the `cornucopia_query_duration_seconds` histogram and the
+/// `cornucopia_query_errors_total` counter, labeled by `query` name and `method`.
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MetricsObserver;
+
+#[cfg(feature = "metrics")]
+impl QueryObserver for MetricsObserver {
+    fn after(&self, info: &QueryInfo, outcome: &QueryOutcome) {
+        let labels = [("query", info.name), ("method", info.method)];
+        metrics::histogram!("cornucopia_query_duration_seconds", &labels)
+            .record(outcome.duration.as_secs_f64());
+        if outcome.error.is_some() {
+            metrics::counter!("cornucopia_query_errors_total", &labels).increment(1);
+        }
+    }
+}
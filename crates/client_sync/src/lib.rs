@@ -1,11 +1,23 @@
 #[doc(hidden)]
 pub mod private;
 
-pub use cornucopia_client_core::{ArrayIterator, ArraySql, BytesSql, IterSql, StringSql};
+pub use crate::mapped_query::MappedQuery;
+pub use crate::row_count::RowCountError;
+pub use cornucopia_client_core::{
+    set_observer, ArrayIterator, ArraySql, BytesSql, FromRow, FromRowBorrowed, IterSql, QueryInfo,
+    QueryObserver, QueryOutcome, StringSql,
+};
 
 #[cfg(feature = "with-serde_json-1")]
 pub use cornucopia_client_core::JsonSql;
 
+#[cfg(feature = "metrics")]
+pub use cornucopia_client_core::MetricsObserver;
+
+mod instrument;
+mod mapped_query;
+mod row_count;
+
 /// This trait allows you to bind parameters to a query using a single
 /// struct, rather than passing each bind parameter as a function parameter.
 pub trait Params<'a, P, O, C> {
@@ -0,0 +1,88 @@
+use postgres::{fallible_iterator::FallibleIterator, types::ToSql, Error, GenericClient, Row};
+
+use crate::{
+    instrument::QuerySpan,
+    private::{slice_iter, Stmt},
+};
+
+/// Query whose rows are mapped by a closure, which can capture its context
+/// and fail with an error merged into the query result.
+///
+/// Obtained from the `map_with` and `try_map` methods of generated queries.
+pub struct MappedQuery<'a, C: GenericClient, F, const N: usize> {
+    client: &'a mut C,
+    params: [&'a (dyn ToSql + Sync); N],
+    stmt: &'a mut Stmt,
+    mapper: F,
+}
+
+impl<'a, C, F, R, E, const N: usize> MappedQuery<'a, C, F, N>
+where
+    C: GenericClient,
+    F: FnMut(&Row) -> Result<R, E>,
+    E: From<Error>,
+{
+    #[doc(hidden)]
+    pub fn new(
+        client: &'a mut C,
+        params: [&'a (dyn ToSql + Sync); N],
+        stmt: &'a mut Stmt,
+        mapper: F,
+    ) -> Self {
+        Self {
+            client,
+            params,
+            stmt,
+            mapper,
+        }
+    }
+
+    pub fn one(mut self) -> Result<R, E> {
+        let span = QuerySpan::new(self.stmt, "one", N);
+        let row = span.in_scope(|| {
+            let stmt = self.stmt.prepare(self.client)?;
+            self.client.query_one(stmt, &self.params)
+        });
+        span.finish(&row, |_| Some(1));
+        span.in_scope(|| (self.mapper)(&row?))
+    }
+
+    pub fn all(mut self) -> Result<Vec<R>, E> {
+        let span = QuerySpan::new(self.stmt, "all", N);
+        let rows = span.in_scope(|| {
+            let stmt = self.stmt.prepare(self.client)?;
+            self.client.query(stmt, &self.params)
+        });
+        span.finish(&rows, |rows| Some(rows.len() as u64));
+        span.in_scope(|| rows?.iter().map(&mut self.mapper).collect())
+    }
+
+    pub fn opt(mut self) -> Result<Option<R>, E> {
+        let span = QuerySpan::new(self.stmt, "opt", N);
+        let row = span.in_scope(|| {
+            let stmt = self.stmt.prepare(self.client)?;
+            self.client.query_opt(stmt, &self.params)
+        });
+        span.finish(&row, |row| Some(row.is_some().into()));
+        span.in_scope(|| row?.map(|row| (self.mapper)(&row)).transpose())
+    }
+
+    pub fn iter(self) -> Result<impl Iterator<Item = Result<R, E>> + 'a, E>
+    where
+        F: 'a,
+        R: 'a,
+        E: 'a,
+    {
+        let span = QuerySpan::new(self.stmt, "iter", N);
+        let rows = span.in_scope(|| {
+            let stmt = self.stmt.prepare(self.client)?;
+            self.client.query_raw(stmt, slice_iter(&self.params))
+        });
+        span.finish(&rows, |_| None);
+        let mut mapper = self.mapper;
+        let it = rows?
+            .iterator()
+            .map(move |res| res.map_err(E::from).and_then(|row| mapper(&row)));
+        Ok(it)
+    }
+}
@@ -1,10 +1,13 @@
 pub use cornucopia_client_core::{slice_iter, Domain, DomainArray};
 
-use postgres::Statement;
+use postgres::{error::SqlState, types::ToSql, Error, GenericClient, Statement};
+
+use crate::{instrument::QuerySpan, RowCountError};
 
 /// Cached statement
 pub struct Stmt {
     query: &'static str,
+    name: Option<&'static str>,
     cached: Option<Statement>,
 }
 
@@ -13,19 +16,69 @@ impl Stmt {
     pub fn new(query: &'static str) -> Self {
         Self {
             query,
+            name: None,
             cached: None,
         }
     }
 
-    pub fn prepare<'a, C: postgres::GenericClient>(
+    /// Statement identified by its module and query names in spans and observers
+    #[must_use]
+    pub fn named(query: &'static str, name: &'static str) -> Self {
+        Self {
+            query,
+            name: Some(name),
+            cached: None,
+        }
+    }
+
+    /// SQL text of the statement
+    pub fn query(&self) -> &'static str {
+        self.query
+    }
+
+    /// Name of the statement, made of its module and query names
+    pub fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+
+    pub fn prepare<'a, C: GenericClient>(
         &'a mut self,
         client: &mut C,
-    ) -> Result<&'a Statement, postgres::Error> {
+    ) -> Result<&'a Statement, Error> {
         if self.cached.is_none() {
-            let stmt = client.prepare(self.query)?;
-            self.cached = Some(stmt);
+            let span = QuerySpan::new(self, "prepare", 0);
+            let stmt = span.in_scope(|| client.prepare(self.query));
+            span.finish(&stmt, |_| None);
+            self.cached = Some(stmt?);
         }
         // the statement is always prepared at this point
         Ok(unsafe { self.cached.as_ref().unwrap_unchecked() })
     }
 }
+
+/// Executes a statement, returning the number of affected rows
+pub fn execute<C: GenericClient>(
+    client: &mut C,
+    stmt: &mut Stmt,
+    params: &[&(dyn ToSql + Sync)],
+) -> Result<u64, Error> {
+    let span = QuerySpan::new(stmt, "execute", params.len());
+    let rows = span.in_scope(|| {
+        let stmt = stmt.prepare(client)?;
+        client.execute(stmt, params)
+    });
+    span.finish(&rows, |rows| Some(*rows));
+    rows
+}
+
+/// Creates the savepoint of an `!expect` query rolling back on mismatch, which only exists
+/// inside a transaction
+pub fn savepoint<C: GenericClient>(client: &mut C) -> Result<(), RowCountError> {
+    match client.execute("SAVEPOINT cornucopia_expect", &[]) {
+        Ok(_) => Ok(()),
+        Err(err) if err.code() == Some(&SqlState::NO_ACTIVE_SQL_TRANSACTION) => {
+            Err(RowCountError::NoTransaction)
+        }
+        Err(err) => Err(err.into()),
+    }
+}
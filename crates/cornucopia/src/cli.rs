@@ -2,7 +2,10 @@ use std::path::PathBuf;
 
 use clap::{Parser, Subcommand};
 
-use crate::{conn, container, error::Error, generate_live, generate_managed, CodegenSettings};
+use crate::{
+    conn, error::Error, generate_cloned, generate_live, generate_managed, CloneTarget,
+    CodegenSettings, ContainerConfig, Derives, Engine,
+};
 
 /// Command line interface to interact with Cornucopia SQL.
 #[derive(Parser, Debug)]
@@ -11,6 +14,36 @@ struct Args {
     /// Use `podman` instead of `docker`
     #[clap(short, long)]
     podman: bool,
+    /// Use a throwaway cluster created by the local `initdb` and `pg_ctl` instead of a container
+    #[clap(long, conflicts_with = "podman")]
+    local: bool,
+    /// Directory containing the local `initdb` and `pg_ctl`, looked up on `PATH` by default
+    #[clap(long, requires = "local")]
+    pg_bin_dir: Option<PathBuf>,
+    /// Image of the managed container, including its tag
+    #[clap(long, default_value = "docker.io/library/postgres:latest")]
+    image: String,
+    /// Host port of the managed container [default: a free port, or 5435 with `--keep-container`]
+    #[clap(long)]
+    port: Option<u16>,
+    /// Name of the managed container [default: a name unique to this run, or `cornucopia_postgres` with `--keep-container`]
+    #[clap(long)]
+    container_name: Option<String>,
+    /// Password of the `postgres` user of the managed container
+    #[clap(long, default_value = "postgres")]
+    container_password: String,
+    /// Additional environment variables of the managed container (e.g. `TZ=UTC`)
+    #[clap(long = "env", value_name = "KEY=VALUE")]
+    envs: Vec<String>,
+    /// Additional arguments given to the container manager's `run` command
+    #[clap(long = "container-arg", value_name = "ARG", allow_hyphen_values = true)]
+    container_args: Vec<String>,
+    /// Keep the managed container running, and reuse it with a fresh database on the next run
+    #[clap(long)]
+    keep_container: bool,
+    /// Extensions created before loading the schema (e.g. `postgis,pg_trgm,citext`)
+    #[clap(long, value_delimiter = ',')]
+    extensions: Vec<String>,
     /// Folder containing the queries
     #[clap(short, long, default_value = "queries/")]
     queries_path: PathBuf,
@@ -28,18 +61,49 @@ struct Args {
     /// Derive serde's `Serialize` trait for generated types.
     #[clap(long)]
     serialize: bool,
+    /// Additional traits to derive on rows (e.g. `serde::Deserialize,Hash`)
+    #[clap(long, value_delimiter = ',')]
+    derive_rows: Vec<String>,
+    /// Additional traits to derive on params
+    #[clap(long, value_delimiter = ',')]
+    derive_params: Vec<String>,
+    /// Additional traits to derive on enums
+    #[clap(long, value_delimiter = ',')]
+    derive_enums: Vec<String>,
+    /// Additional traits to derive on composite types
+    #[clap(long, value_delimiter = ',')]
+    derive_composites: Vec<String>,
+    /// Generate typestate builders setting query params by name
+    #[clap(long)]
+    builder: bool,
+    /// Generate a repository trait per query module, implemented using a client
+    #[clap(long)]
+    repository: bool,
 }
 
 #[derive(Debug, Subcommand)]
 enum Action {
     /// Generate your modules against your own db
     Live {
-        /// Postgres url to the database
-        url: String,
+        /// Postgres url or `key=value` connection string to the database, defaults to
+        /// `DATABASE_URL`. Settings it lacks are taken from its service, the libpq environment
+        /// variables and `.pgpass`
+        url: Option<String>,
+    },
+    /// Generate your modules against a schema-only copy of your own db, without modifying it
+    Clone {
+        /// Postgres url or `key=value` connection string to the database whose schema is
+        /// copied, defaults to `DATABASE_URL`. Settings it lacks are taken from its service,
+        /// the libpq environment variables and `.pgpass`
+        url: Option<String>,
+        /// Postgres url to a server where a scratch database receives the copy,
+        /// instead of a managed container
+        #[clap(long)]
+        scratch_url: Option<String>,
     },
     /// Generate your modules against schema files
     Schema {
-        /// SQL files containing the database schema
+        /// SQL files containing the database schema, or folders of migrations
         schema_files: Vec<PathBuf>,
     },
 }
@@ -48,37 +112,86 @@ enum Action {
 pub fn run() -> Result<(), Error> {
     let Args {
         podman,
+        local,
+        pg_bin_dir,
+        image,
+        port,
+        container_name,
+        container_password,
+        envs,
+        container_args,
+        keep_container,
+        extensions,
         queries_path,
         destination,
         action,
         sync,
         r#async,
         serialize,
+        derive_rows,
+        derive_params,
+        derive_enums,
+        derive_composites,
+        builder,
+        repository,
     } = Args::parse();
 
     let settings = CodegenSettings {
         gen_async: r#async || !sync,
         gen_sync: sync,
         derive_ser: serialize,
+        derives: Derives {
+            rows: derive_rows,
+            params: derive_params,
+            enums: derive_enums,
+            composites: derive_composites,
+        },
+        gen_builder: builder,
+        gen_repository: repository,
+    };
+
+    let engine = if local {
+        Engine::Local {
+            bin_dir: pg_bin_dir,
+        }
+    } else if podman {
+        Engine::Podman
+    } else {
+        Engine::Docker
+    };
+    let container = ContainerConfig {
+        engine,
+        image,
+        port,
+        name: container_name,
+        password: container_password,
+        env: envs,
+        args: container_args,
+        extensions,
+        keep: keep_container,
     };
 
     match action {
         Action::Live { url } => {
-            let mut client = conn::from_url(&url)?;
+            let mut client = conn::from_url(url.as_deref())?;
             generate_live(&mut client, &queries_path, Some(&destination), settings)?;
         }
+        Action::Clone { url, scratch_url } => {
+            let target = match &scratch_url {
+                Some(scratch_url) => CloneTarget::Scratch(scratch_url),
+                None => CloneTarget::Container(&container),
+            };
+            let url = conn::database_url(url.as_deref());
+            generate_cloned(queries_path, &url, Some(destination), target, settings)?;
+        }
         Action::Schema { schema_files } => {
-            // Run the generate command. If the command is unsuccessful, cleanup Cornucopia's container
-            if let Err(e) = generate_managed(
+            generate_managed(
                 queries_path,
                 &schema_files,
                 Some(destination),
-                podman,
+                &container,
                 settings,
-            ) {
-                container::cleanup(podman).ok();
-                return Err(e);
-            }
+            )?;
         }
     };
     Ok(())
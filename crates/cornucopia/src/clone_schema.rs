@@ -0,0 +1,203 @@
+use std::{
+    process::{Command, Stdio},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use postgres::Client;
+
+use crate::{conn, container::ContainerConfig, load_schema::load_script};
+
+use self::error::Error;
+
+/// Database receiving the schema-only copy of a live database.
+#[derive(Debug, Clone, Copy)]
+pub enum CloneTarget<'a> {
+    /// A container managed by Cornucopia
+    Container(&'a ContainerConfig),
+    /// A scratch database created, then dropped, on the server at this URL
+    Scratch(&'a str),
+}
+
+/// Copies the schema of the live database at `url` into a database, using `pg_dump`.
+///
+/// Only the schema is copied (types, tables, functions, etc.), without data,
+/// owners or privileges. The live database is only read from.
+///
+/// `pg_dump` can't dump servers of a newer major version than its own, which is checked
+/// beforehand.
+pub fn clone_schema(client: &mut Client, url: &str) -> Result<(), Error> {
+    let (config, tls) = conn::parse_url(url, &conn::process_env)?;
+    check_pg_dump_version(&config, &tls)?;
+    let mut command = Command::new("pg_dump");
+    // The password is given through the environment to keep it out of the process list
+    if let Some(password) = config.get_password() {
+        command.env("PGPASSWORD", String::from_utf8_lossy(password).as_ref());
+    }
+    let output = command
+        .args([
+            "--schema-only",
+            "--no-owner",
+            "--no-privileges",
+            "--no-comments",
+            "--no-publications",
+            "--no-subscriptions",
+            "--no-security-labels",
+            "--no-tablespaces",
+        ])
+        .arg(format!("--dbname={}", conn::conninfo(&config, &tls)))
+        .stdin(Stdio::null())
+        .stderr(Stdio::piped())
+        .stdout(Stdio::piped())
+        .output()
+        .map_err(|err| Error::Dump {
+            msg: format!("{err:#}"),
+        })?;
+    if !output.status.success() {
+        return Err(Error::Dump {
+            msg: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    let sql = String::from_utf8_lossy(&output.stdout).into_owned();
+    load_script(client, "pg_dump", sql)?;
+    Ok(())
+}
+
+/// Checks that `pg_dump` supports the major version of the live database server.
+fn check_pg_dump_version(config: &postgres::Config, tls: &conn::TlsParams) -> Result<(), Error> {
+    let output = Command::new("pg_dump")
+        .arg("--version")
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|err| Error::Dump {
+            msg: format!("{err:#}"),
+        })?;
+    let version = String::from_utf8_lossy(&output.stdout);
+    let pg_dump = version_num(&version).ok_or_else(|| Error::Dump {
+        msg: format!("unexpected `pg_dump --version` output `{}`", version.trim()),
+    })?;
+    let server: String = conn::connect(config, tls)?
+        .query_one("SHOW server_version_num", &[])
+        .and_then(|row| row.try_get(0))
+        .map_err(|err| Error::Dump {
+            msg: format!("{err:#}"),
+        })?;
+    let server = server.parse().unwrap_or(0);
+    if major_version(server) > major_version(pg_dump) {
+        return Err(Error::DumpVersion {
+            pg_dump: major_version(pg_dump),
+            server: major_version(server),
+        });
+    }
+    Ok(())
+}
+
+/// Version number, formatted like `server_version_num`, of a `pg_dump --version` output
+/// (e.g. `pg_dump (PostgreSQL) 15.4 (Debian 15.4-1)`)
+fn version_num(output: &str) -> Option<u32> {
+    let version = output.split_whitespace().nth(2)?;
+    let mut parts = version.split('.').map(|part| {
+        let end = part
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(part.len());
+        part[..end].parse::<u32>().ok()
+    });
+    let major = parts.next()??;
+    let minor = parts.next().flatten().unwrap_or(0);
+    Some(if major >= 10 {
+        major * 10000 + minor
+    } else {
+        let patch = parts.next().flatten().unwrap_or(0);
+        major * 10000 + minor * 100 + patch
+    })
+}
+
+/// Major version of a version number, made of its first two parts before Postgres 10
+/// (e.g. 906 for 9.6)
+fn major_version(num: u32) -> u32 {
+    if num >= 100000 {
+        num / 10000
+    } else {
+        num / 100
+    }
+}
+
+/// Creates a scratch database on the server at `url`, calls `f` with a connection to it,
+/// then drops it.
+pub(crate) fn with_scratch_db<T>(
+    url: &str,
+    f: impl FnOnce(&mut Client) -> Result<T, crate::Error>,
+) -> Result<T, crate::Error> {
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+    let count = COUNT.fetch_add(1, Ordering::Relaxed);
+    let name = format!("cornucopia_scratch_{}_{count}", std::process::id());
+
+    let mut admin = conn::from_url(Some(url))?;
+    let scratch_err = |action, err: postgres::Error| Error::Scratch {
+        action,
+        name: name.clone(),
+        msg: format!("{err:#}"),
+    };
+    admin
+        .batch_execute(&format!("CREATE DATABASE \"{name}\""))
+        .map_err(|err| scratch_err("create", err))?;
+    let result = conn::parse_url(url, &conn::process_env)
+        .and_then(|(mut config, tls)| conn::connect(config.dbname(&name), &tls))
+        .map_err(crate::Error::from)
+        .and_then(|mut client| f(&mut client));
+    // The scratch connection is closed at this point, Postgres waits for its backend to exit
+    let dropped = admin
+        .batch_execute(&format!("DROP DATABASE \"{name}\""))
+        .map_err(|err| scratch_err("drop", err));
+    let value = result?;
+    dropped?;
+    Ok(value)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{major_version, version_num};
+
+    #[test]
+    fn pg_dump_version() {
+        let version = |output| version_num(output).map(major_version);
+        assert_eq!(
+            version("pg_dump (PostgreSQL) 15.4 (Debian 15.4-1.pgdg120+1)\n"),
+            Some(15)
+        );
+        assert_eq!(version("pg_dump (PostgreSQL) 17beta2"), Some(17));
+        assert_eq!(version("pg_dump (PostgreSQL) 9.6.24"), Some(906));
+        assert_eq!(version("pg_dump: command not found"), None);
+        // Same format as `server_version_num`
+        assert_eq!(version_num("pg_dump (PostgreSQL) 9.6.24"), Some(90624));
+        assert_eq!(major_version(160002), 16);
+    }
+}
+
+pub(crate) mod error {
+    use miette::Diagnostic;
+    use thiserror::Error as ThisError;
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    pub enum Error {
+        #[error("Could not dump the schema of the live database: {msg}")]
+        #[diagnostic(help(
+            "check that `pg_dump` is installed, and that its version is not older than the live database's"
+        ))]
+        Dump { msg: String },
+        #[error("`pg_dump` {pg_dump} can't dump the live database, whose server runs the newer Postgres {server}")]
+        #[diagnostic(help("install `pg_dump` {server} or newer"))]
+        DumpVersion { pg_dump: u32, server: u32 },
+        #[error("Could not {action} scratch database `{name}`: {msg}")]
+        Scratch {
+            action: &'static str,
+            name: String,
+            msg: String,
+        },
+        #[error(transparent)]
+        #[diagnostic(transparent)]
+        Conn(#[from] crate::conn::error::Error),
+        #[error(transparent)]
+        #[diagnostic(transparent)]
+        Load(#[from] crate::load_schema::error::Error),
+    }
+}
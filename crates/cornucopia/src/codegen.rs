@@ -3,13 +3,16 @@ use std::fmt::{Display, Write};
 
 use codegen_template::code;
 use indexmap::IndexMap;
+use postgres_types::Type;
 
 use crate::{
+    parser::{Attributes, Cardinality, Expect},
     prepare_queries::{
         Ident, Preparation, PreparedContent, PreparedField, PreparedItem, PreparedModule,
         PreparedQuery, PreparedType,
     },
-    CodegenSettings,
+    type_registrar::CornucopiaType,
+    CodegenSettings, Derives,
 };
 
 pub struct GenCtx {
@@ -99,6 +102,138 @@ impl PreparedField {
     }
 }
 
+impl PreparedItem {
+    pub fn vis(&self) -> &str {
+        self.attributes
+            .vis
+            .as_ref()
+            .map_or("pub", |it| it.value.as_str())
+    }
+}
+
+/// Merges the `base` derives with the ones requested in `attributes`, skipping duplicates.
+fn derives(base: &[&str], attributes: &Attributes) -> String {
+    let mut derives: Vec<&str> = Vec::new();
+    for derive in base
+        .iter()
+        .copied()
+        .filter(|it| !it.is_empty())
+        .chain(attributes.derives())
+    {
+        if derives
+            .iter()
+            .all(|it| trait_name(it) != trait_name(derive))
+        {
+            derives.push(derive);
+        }
+    }
+    derives.join(", ")
+}
+
+/// Name of a derived trait without its path
+fn trait_name(derive: &str) -> &str {
+    derive.rsplit("::").next().unwrap_or(derive).trim()
+}
+
+/// Configured derives, filtered by the traits implemented by the fields of each item.
+struct DeriveCtx<'a> {
+    derives: &'a Derives,
+    derive_ser: bool,
+    types: &'a IndexMap<String, Vec<PreparedType>>,
+}
+
+impl<'a> DeriveCtx<'a> {
+    /// Configured derives supported by all `fields`
+    fn supported<'b>(
+        &self,
+        derives: &'b [String],
+        fields: &[PreparedField],
+        is_params: bool,
+    ) -> Vec<&'b str> {
+        derives
+            .iter()
+            .map(String::as_str)
+            .filter(|derive| {
+                fields.iter().all(|f| {
+                    if is_params {
+                        self.param_implements(&f.ty, derive)
+                    } else {
+                        self.implements(&f.ty, derive)
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// Configured derives of a custom type
+    fn custom_derives(&self, prepared: &'a PreparedType) -> Vec<&'a str> {
+        match &prepared.content {
+            PreparedContent::Enum(_) => self.derives.enums.iter().map(String::as_str).collect(),
+            PreparedContent::Composite(fields) => {
+                self.supported(&self.derives.composites, fields, false)
+            }
+        }
+    }
+
+    /// Does the owned rust type of `ty` implement the derived trait
+    fn implements(&self, ty: &CornucopiaType, derive: &str) -> bool {
+        let name = trait_name(derive);
+        match ty {
+            CornucopiaType::Simple { pg_ty, is_copy, .. } => {
+                let is_float = matches!(*pg_ty, Type::FLOAT4 | Type::FLOAT8);
+                let is_json = matches!(*pg_ty, Type::JSON | Type::JSONB);
+                match name {
+                    "Copy" => *is_copy,
+                    "Eq" => !is_float,
+                    "Hash" | "Ord" => !is_float && !is_json,
+                    "PartialOrd" => !is_json,
+                    _ => true,
+                }
+            }
+            CornucopiaType::Array { inner } => name != "Copy" && self.implements(inner, derive),
+            CornucopiaType::Domain { inner, .. } => self.implements(inner, derive),
+            CornucopiaType::Custom { pg_ty, .. } => self
+                .types
+                .get(pg_ty.schema())
+                .and_then(|types| types.iter().find(|it| it.name == pg_ty.name()))
+                .map_or(false, |prepared| {
+                    let base: &[&str] = match prepared.content {
+                        PreparedContent::Enum(_) => &["Debug", "Clone", "Copy", "PartialEq", "Eq"],
+                        PreparedContent::Composite(_) if prepared.is_copy => {
+                            &["Debug", "Clone", "Copy", "PartialEq"]
+                        }
+                        PreparedContent::Composite(_) => &["Debug", "Clone", "PartialEq"],
+                    };
+                    (self.derive_ser && name == "Serialize")
+                        || base
+                            .iter()
+                            .chain(&self.custom_derives(prepared))
+                            .any(|it| trait_name(it) == name)
+                }),
+        }
+    }
+
+    /// Does the parameter type of `ty` implement the derived trait, generic
+    /// parameters only bounding the generated implementation.
+    fn param_implements(&self, ty: &CornucopiaType, derive: &str) -> bool {
+        match ty {
+            CornucopiaType::Simple { pg_ty, .. }
+                if matches!(
+                    *pg_ty,
+                    Type::BYTEA | Type::TEXT | Type::VARCHAR | Type::JSON | Type::JSONB
+                ) =>
+            {
+                true
+            }
+            CornucopiaType::Array { .. } => true,
+            CornucopiaType::Domain { inner, .. } => self.param_implements(inner, derive),
+            // Borrowed composite types only implement `Debug`
+            CornucopiaType::Custom { is_copy: false, .. } => false,
+            _ => self.implements(ty, derive),
+        }
+    }
+}
+
 fn enum_sql(w: &mut impl Write, name: &str, enum_name: &str, variants: &[Ident]) {
     let enum_names = std::iter::repeat(enum_name);
     let db_variants_ident = variants.iter().map(|v| &v.db);
@@ -304,19 +439,30 @@ fn composite_fromsql(
     );
 }
 
-fn gen_params_struct(w: &mut impl Write, params: &PreparedItem, ctx: &GenCtx) {
+fn gen_params_struct(
+    w: &mut impl Write,
+    params: &PreparedItem,
+    ctx: &GenCtx,
+    derive_ctx: &DeriveCtx,
+) {
     let PreparedItem {
         name,
         fields,
         is_copy,
         is_named,
         is_ref,
+        attributes,
         ..
     } = params;
     if *is_named {
         let traits = &mut Vec::new();
 
-        let copy = if *is_copy { "Clone,Copy," } else { "" };
+        let copy: &[&str] = if *is_copy { &["Clone", "Copy"] } else { &[] };
+        let configured = derive_ctx.supported(&derive_ctx.derives.params, fields, true);
+        let derives = derives(&[copy, &["Debug"], &configured].concat(), attributes);
+        let attrs: Vec<_> = attributes.struct_attrs().collect();
+        let attrs = attrs.iter();
+        let vis = params.vis();
         let lifetime = if *is_ref { "'a," } else { "" };
         let fields_ty = fields
             .iter()
@@ -325,20 +471,40 @@ fn gen_params_struct(w: &mut impl Write, params: &PreparedItem, ctx: &GenCtx) {
         let fields_name = fields.iter().map(|p| &p.ident.rs);
         let traits_idx = (1..=traits.len()).map(idx_char);
         code!(w =>
-            #[derive($copy Debug)]
-            pub struct $name<$lifetime $($traits_idx: $traits,)> {
+            #[derive($derives)]
+            $(#[$attrs])
+            $vis struct $name<$lifetime $($traits_idx: $traits,)> {
+                $(pub $fields_name: $fields_ty,)
+            }
+        );
+
+        // Owned params, 'static and Send to be moved across threads and tasks
+        let configured = derive_ctx.supported(&derive_ctx.derives.params, fields, false);
+        let owned_derives = self::derives(
+            &[&["Debug", "Clone"], configured.as_slice()].concat(),
+            attributes,
+        );
+        let attrs: Vec<_> = attributes.struct_attrs().collect();
+        let attrs = attrs.iter();
+        let fields_name = fields.iter().map(|p| &p.ident.rs);
+        let fields_ty = fields.iter().map(|p| p.own_struct(ctx));
+        code!(w =>
+            #[derive($owned_derives)]
+            $(#[$attrs])
+            $vis struct ${name}Owned {
                 $(pub $fields_name: $fields_ty,)
             }
         );
     }
 }
 
-fn gen_row_structs(w: &mut impl Write, row: &PreparedItem, ctx: &GenCtx) {
+fn gen_row_structs(w: &mut impl Write, row: &PreparedItem, ctx: &GenCtx, derive_ctx: &DeriveCtx) {
     let PreparedItem {
         name,
         fields,
         is_copy,
         is_named,
+        attributes,
         ..
     } = row;
     if *is_named {
@@ -347,13 +513,26 @@ fn gen_row_structs(w: &mut impl Write, row: &PreparedItem, ctx: &GenCtx) {
         let fields_ty = fields.iter().map(|p| p.own_struct(ctx));
         let copy = if *is_copy { "Copy" } else { "" };
         let ser_str = if ctx.gen_derive {
-            "serde::Serialize,"
+            "serde::Serialize"
         } else {
             ""
         };
+        let configured = derive_ctx.supported(&derive_ctx.derives.rows, fields, false);
+        let derives = derives(
+            &[
+                &[ser_str, "Debug", "Clone", "PartialEq", copy],
+                configured.as_slice(),
+            ]
+            .concat(),
+            attributes,
+        );
+        let attrs: Vec<_> = attributes.struct_attrs().collect();
+        let attrs = attrs.iter();
+        let vis = row.vis();
         code!(w =>
-            #[derive($ser_str Debug, Clone, PartialEq,$copy)]
-            pub struct $name {
+            #[derive($derives)]
+            $(#[$attrs])
+            $vis struct $name {
                 $(pub $fields_name : $fields_ty,)
             }
         );
@@ -362,10 +541,14 @@ fn gen_row_structs(w: &mut impl Write, row: &PreparedItem, ctx: &GenCtx) {
             let fields_name = fields.iter().map(|p| &p.ident.rs);
             let fields_ty = fields.iter().map(|p| p.brw_ty(true, ctx));
             let from_own_assign = fields.iter().map(|f| f.owning_assign());
+            let cfg: Vec<_> = attributes.cfg_attrs().collect();
+            let cfg = cfg.iter();
             code!(w =>
-                pub struct ${name}Borrowed<'a> {
+                $(#[$cfg])
+                $vis struct ${name}Borrowed<'a> {
                     $(pub $fields_name : $fields_ty,)
                 }
+                $(#[$cfg])
                 impl<'a> From<${name}Borrowed<'a>> for $name {
                     fn from(${name}Borrowed { $($fields_name,) }: ${name}Borrowed<'a>) -> Self {
                         Self {
@@ -375,45 +558,87 @@ fn gen_row_structs(w: &mut impl Write, row: &PreparedItem, ctx: &GenCtx) {
                 }
             );
         };
+        gen_from_row(w, row, ctx);
     }
 }
 
-fn gen_row_query(w: &mut impl Write, row: &PreparedItem, ctx: &GenCtx) {
+/// Implements the `FromRow` traits, decoding each field from the column of the same name.
+fn gen_from_row(w: &mut impl Write, row: &PreparedItem, ctx: &GenCtx) {
     let PreparedItem {
         name,
+        fields,
+        is_copy,
+        attributes,
+        ..
+    } = row;
+    let backend = if ctx.is_async {
+        "tokio_postgres"
+    } else {
+        "postgres"
+    };
+    let client = ctx.client_name();
+    let cfg: Vec<_> = attributes.cfg_attrs().collect();
+    let cfg = cfg.iter();
+    let fields_name = fields.iter().map(|p| &p.ident.rs);
+    let columns_name = fields.iter().map(|p| format!("{:?}", p.ident.db));
+    if *is_copy {
+        code!(w =>
+            $(#[$cfg])
+            impl $client::FromRow for $name {
+                fn from_row(row: &$backend::Row) -> Result<Self, $backend::Error> {
+                    Ok(Self {
+                        $($fields_name: row.try_get($columns_name)?,)
+                    })
+                }
+            }
+        );
+    } else {
+        let cfg_own = cfg.clone();
+        code!(w =>
+            $(#[$cfg])
+            impl<'a> $client::FromRowBorrowed<'a> for ${name}Borrowed<'a> {
+                fn from_row_borrowed(row: &'a $backend::Row) -> Result<Self, $backend::Error> {
+                    Ok(Self {
+                        $($fields_name: row.try_get($columns_name)?,)
+                    })
+                }
+            }
+            $(#[$cfg_own])
+            impl $client::FromRow for $name {
+                fn from_row(row: &$backend::Row) -> Result<Self, $backend::Error> {
+                    <${name}Borrowed as $client::FromRowBorrowed>::from_row_borrowed(row).map(Into::into)
+                }
+            }
+        );
+    }
+}
+
+fn gen_row_query(w: &mut impl Write, row: &PreparedItem, ctx: &GenCtx) {
+    let PreparedItem {
         fields,
         is_copy,
         is_named,
+        attributes,
         ..
     } = row;
+    let name = row.type_name();
+    let cfg: Vec<_> = attributes.cfg_attrs().collect();
+    let cfg = cfg.iter();
+    let vis = row.vis();
     // Generate query struct
     let borrowed_str = if *is_copy { "" } else { "Borrowed" };
-    let (client_mut, fn_async, fn_await, backend, collect, raw_type, raw_pre, raw_post, client) =
-        if ctx.is_async {
-            (
-                "",
-                "async",
-                ".await",
-                "tokio_postgres",
-                "try_collect().await",
-                "futures::Stream",
-                "",
-                ".into_stream()",
-                "cornucopia_async",
-            )
-        } else {
-            (
-                "mut",
-                "",
-                "",
-                "postgres",
-                "collect()",
-                "Iterator",
-                ".iterator()",
-                "",
-                "cornucopia_sync",
-            )
-        };
+    let (client_mut, fn_async, fn_await, backend, raw_type, client) = if ctx.is_async {
+        (
+            "",
+            "async",
+            ".await",
+            "tokio_postgres",
+            "futures::Stream",
+            "cornucopia_async",
+        )
+    } else {
+        ("mut", "", "", "postgres", "Iterator", "cornucopia_sync")
+    };
 
     let row_struct = if *is_named {
         format!("{}{borrowed_str}", row.path(ctx))
@@ -422,13 +647,15 @@ fn gen_row_query(w: &mut impl Write, row: &PreparedItem, ctx: &GenCtx) {
     };
 
     code!(w =>
-    pub struct ${name}Query<'a, C: GenericClient, T, const N: usize> {
+    $(#[$cfg])
+    $vis struct ${name}Query<'a, C: GenericClient, T, const N: usize> {
         client: &'a $client_mut C,
         params: [&'a (dyn postgres_types::ToSql + Sync); N],
         stmt: &'a mut $client::private::Stmt,
-        extractor: fn(&$backend::Row) -> $row_struct,
+        extractor: fn(&$backend::Row) -> Result<$row_struct, $backend::Error>,
         mapper: fn($row_struct) -> T,
     }
+    $(#[$cfg])
     impl<'a, C, T:'a, const N: usize> ${name}Query<'a, C, T, N> where C: GenericClient {
         pub fn map<R>(self, mapper: fn($row_struct) -> R) -> ${name}Query<'a,C,R,N> {
             ${name}Query {
@@ -440,37 +667,43 @@ fn gen_row_query(w: &mut impl Write, row: &PreparedItem, ctx: &GenCtx) {
             }
         }
 
+        pub fn map_with<R, F: FnMut($row_struct) -> R>(
+            self,
+            mut mapper: F,
+        ) -> $client::MappedQuery<'a, C, impl FnMut(&$backend::Row) -> Result<R, $backend::Error>, N> {
+            self.try_map(move |it| Ok(mapper(it)))
+        }
+
+        pub fn try_map<R, E: From<$backend::Error>, F: FnMut($row_struct) -> Result<R, E>>(
+            self,
+            mut mapper: F,
+        ) -> $client::MappedQuery<'a, C, impl FnMut(&$backend::Row) -> Result<R, E>, N> {
+            let extractor = self.extractor;
+            $client::MappedQuery::new(self.client, self.params, self.stmt, move |row| {
+                mapper(extractor(row)?)
+            })
+        }
+
         pub $fn_async fn one(self) -> Result<T, $backend::Error> {
-            let stmt = self.stmt.prepare(self.client)$fn_await?;
-            let row = self.client.query_one(stmt, &self.params)$fn_await?;
-            Ok((self.mapper)((self.extractor)(&row)))
+            let mapper = self.mapper;
+            self.map_with(mapper).one()$fn_await
         }
 
         pub $fn_async fn all(self) -> Result<Vec<T>, $backend::Error> {
-            self.iter()$fn_await?.$collect
+            let mapper = self.mapper;
+            self.map_with(mapper).all()$fn_await
         }
 
         pub $fn_async fn opt(self) -> Result<Option<T>, $backend::Error> {
-            let stmt = self.stmt.prepare(self.client)$fn_await?;
-            Ok(self
-                .client
-                .query_opt(stmt, &self.params)
-                $fn_await?
-                .map(|row| (self.mapper)((self.extractor)(&row))))
+            let mapper = self.mapper;
+            self.map_with(mapper).opt()$fn_await
         }
 
         pub $fn_async fn iter(
             self,
         ) -> Result<impl $raw_type<Item = Result<T, $backend::Error>> + 'a, $backend::Error> {
-            let stmt = self.stmt.prepare(self.client)$fn_await?;
-            let it = self
-                .client
-                .query_raw(stmt, $client::private::slice_iter(&self.params))
-                $fn_await?
-                $raw_pre
-                .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))))
-                $raw_post;
-            Ok(it)
+            let mapper = self.mapper;
+            self.map_with(mapper).iter()$fn_await
         }
     });
 }
@@ -479,12 +712,24 @@ pub fn idx_char(idx: usize) -> String {
     format!("T{idx}")
 }
 
-fn gen_query_fn<W: Write>(w: &mut W, module: &PreparedModule, query: &PreparedQuery, ctx: &GenCtx) {
+/// Generates the statement of a query and its bind methods, with a typestate builder when
+/// `gen_builder` is set, returning the method of the module repository when `gen_repository` is set.
+fn gen_query_fn(
+    w: &mut String,
+    module: &PreparedModule,
+    query: &PreparedQuery,
+    ctx: &GenCtx,
+    gen_builder: bool,
+    gen_repository: bool,
+) -> Option<RepositoryMethod> {
     let PreparedQuery {
         ident,
         row,
         sql,
         param,
+        attributes,
+        cardinality,
+        expect,
     } = query;
 
     let (client_mut, fn_async, fn_await, backend, client) = if ctx.is_async {
@@ -508,84 +753,291 @@ fn gen_query_fn<W: Write>(w: &mut W, module: &PreparedModule, query: &PreparedQu
         .collect();
     let params_name = order.iter().map(|idx| &param_field[*idx].ident.rs);
     let traits_idx = (1..=traits.len()).map(idx_char);
-    let lazy_impl = |w: &mut W| {
+    let row_item = row
+        .as_ref()
+        .map(|(idx, _)| module.rows.get_index(*idx).unwrap().1);
+    // Items must not be more visible than the structs they expose
+    let vis = if attributes.is_crate_visible()
+        || param.map_or(false, |it| it.attributes.is_crate_visible())
+        || row_item.map_or(false, |it| it.attributes.is_crate_visible())
+    {
+        "pub(crate)"
+    } else {
+        "pub"
+    };
+    let cfg: Vec<_> = attributes.cfg_attrs().collect();
+    let cfg = cfg.iter();
+    // Cardinality markers replace the query builder by a direct fn
+    let direct_ret = row_item.and_then(|item| {
+        let row_struct = if item.is_named {
+            item.path(ctx)
+        } else {
+            item.fields[0].own_struct(ctx)
+        };
+        match cardinality {
+            Some(Cardinality::One) => Some(row_struct),
+            Some(Cardinality::Opt) => Some(format!("Option<{row_struct}>")),
+            Some(Cardinality::Many) => Some(format!("Vec<{row_struct}>")),
+            Some(Cardinality::ExecRows) | None => None,
+        }
+    });
+    // Execute queries with an expected row count use their own error type
+    let error = if expect.is_some() {
+        format!("{client}::RowCountError")
+    } else {
+        format!("{backend}::Error")
+    };
+    // Queries returning rows without cardinality marker use a query builder
+    let is_builder = row.is_some() && direct_ret.is_none();
+    let bind_ret = if let Some((idx, _)) = row {
+        let item = module.rows.get_index(*idx).unwrap().1;
+        let row_struct_name = if item.is_named {
+            item.path(ctx)
+        } else {
+            item.fields[0].own_struct(ctx)
+        };
+        match &direct_ret {
+            Some(ret) => format!("Result<{ret}, {backend}::Error>"),
+            None => format!(
+                "{}Query<'a, C, {row_struct_name}, {}>",
+                item.type_name(),
+                param_field.len()
+            ),
+        }
+    } else {
+        format!("Result<u64, {error}>")
+    };
+    // Body of the bind fn, binding each param from an expression borrowing its value
+    let gen_body = |w: &mut String, stmt: &str, values: &[String]| {
+        let params_wrap = order
+            .iter()
+            .zip(values)
+            .map(|(idx, value)| param_field[*idx].ty.sql_wrapped(value, ctx));
         if let Some((idx, index)) = row {
             let item = module.rows.get_index(*idx).unwrap().1;
             let PreparedItem {
-                name: row_name,
                 fields,
                 is_copy,
                 is_named,
                 ..
             } = &item;
-            // Query fn
-            let nb_params = param_field.len();
+            let row_name = item.type_name();
 
             // TODO find a way to clean this mess
             #[allow(clippy::type_complexity)]
-            let (row_struct_name, extractor, mapper): (_, Box<dyn Fn(&mut W)>, _) = if *is_named {
-                let path = item.path(ctx);
-                (
-                    path.clone(),
-                    Box::new(|w: _| {
-                        let path = item.path(ctx);
-                        let post = if *is_copy { "" } else { "Borrowed" };
-                        let fields_name = fields.iter().map(|p| &p.ident.rs);
-                        let fields_idx = (0..fields.len()).map(|i| index[i]);
-                        code!(w => $path$post {
-                            $($fields_name: row.get($fields_idx),)
-                        })
-                    }),
-                    code!(<$path>::from(it)),
-                )
+            let (row_struct_name, extractor, mapper): (_, Box<dyn Fn(&mut String)>, _) =
+                if *is_named {
+                    let path = item.path(ctx);
+                    (
+                        path.clone(),
+                        Box::new(|w: _| {
+                            let path = item.path(ctx);
+                            if item.is_external {
+                                // External structs have no generated `FromRow` impl
+                                let fields_name = fields.iter().map(|p| &p.ident.rs);
+                                let fields_idx = (0..fields.len()).map(|i| index[i]);
+                                code!(w => Ok($path {
+                                    $($fields_name: row.try_get($fields_idx)?,)
+                                }))
+                            } else if *is_copy {
+                                code!(w => <$path as $client::FromRow>::from_row(row))
+                            } else {
+                                code!(w => <${path}Borrowed as $client::FromRowBorrowed>::from_row_borrowed(row))
+                            }
+                        }),
+                        code!(<$path>::from(it)),
+                    )
+                } else {
+                    let field = &fields[0];
+                    (
+                        field.own_struct(ctx),
+                        Box::new(|w: _| code!(w => row.try_get(0))),
+                        field.owning_call(Some("it")),
+                    )
+                };
+            if direct_ret.is_some() {
+                // Direct fn
+                let row_brw = if !*is_named {
+                    fields[0].brw_ty(false, ctx)
+                } else if *is_copy {
+                    row_struct_name.clone()
+                } else {
+                    format!("{row_struct_name}Borrowed")
+                };
+                let method = match cardinality {
+                    Some(Cardinality::One) => "one",
+                    Some(Cardinality::Opt) => "opt",
+                    _ => "all",
+                };
+                code!(w =>
+                    let extractor = |row: &$backend::Row| {
+                        let it: $row_brw = $!extractor?;
+                        Ok::<$row_struct_name, $backend::Error>($mapper)
+                    };
+                    $client::MappedQuery::new(client, [ $($params_wrap,) ], &mut $stmt, extractor).$method()$fn_await
+                );
             } else {
-                let field = &fields[0];
-                (
-                    field.own_struct(ctx),
-                    Box::new(|w: _| code!(w => row.get(0))),
-                    field.owning_call(Some("it")),
-                )
-            };
-            code!(w =>
-                pub fn bind<'a, C: GenericClient,$($traits_idx: $traits,)>(&'a mut self, client: &'a $client_mut C, $($params_name: &'a $params_ty,) ) -> ${row_name}Query<'a,C, $row_struct_name, $nb_params> {
+                let values = values.iter();
+                code!(w =>
                     ${row_name}Query {
                         client,
-                        params: [$($params_name,)],
-                        stmt: &mut self.0,
+                        params: [$($values,)],
+                        stmt: &mut $stmt,
                         extractor: |row| { $!extractor },
                         mapper: |it| { $mapper },
                     }
+                );
+            }
+        } else if let Some(Expect { rows, rollback }) = expect {
+            // Execute fn
+            let (savepoint, rollback, release) = if *rollback {
+                (
+                    format!("{client}::private::savepoint(client){fn_await}?;"),
+                    format!("client.execute(\"ROLLBACK TO SAVEPOINT cornucopia_expect\", &[]){fn_await}?;"),
+                    format!("client.execute(\"RELEASE SAVEPOINT cornucopia_expect\", &[]){fn_await}?;"),
+                )
+            } else {
+                (String::new(), String::new(), String::new())
+            };
+            code!(w =>
+                $stmt.prepare(client)$fn_await?;
+                $savepoint
+                let actual = $client::private::execute(client, &mut $stmt, &[ $($params_wrap,) ])$fn_await?;
+                if actual != $rows {
+                    $rollback
+                    $release
+                    return Err($client::RowCountError::Unexpected { expected: $rows, actual });
                 }
+                $release
+                Ok(actual)
             );
         } else {
-            // Execute fn
-            let params_wrap = order.iter().map(|idx| {
-                let p = &param_field[*idx];
-                p.ty.sql_wrapped(&p.ident.rs, ctx)
-            });
             code!(w =>
-                pub $fn_async fn bind<'a, C: GenericClient,$($traits_idx: $traits,)>(&'a mut self, client: &'a $client_mut C, $($params_name: &'a $params_ty,)) -> Result<u64, $backend::Error> {
-                    let stmt = self.0.prepare(client)$fn_await?;
-                    client.execute(stmt, &[ $($params_wrap,) ])$fn_await
-                }
+                $client::private::execute(client, &mut $stmt, &[ $($params_wrap,) ])$fn_await
             );
         }
     };
+    let lazy_impl = |w: &mut String| {
+        let values: Vec<_> = order
+            .iter()
+            .map(|idx| param_field[*idx].ident.rs.clone())
+            .collect();
+        let body = |w: &mut String| gen_body(w, "self.0", &values);
+        let fn_async = if is_builder { "" } else { fn_async };
+        code!(w =>
+            pub $fn_async fn bind<'a, C: GenericClient,$($traits_idx: $traits,)>(&'a mut self, client: &'a $client_mut C, $($params_name: &'a $params_ty,)) -> $bind_ret {
+                $!body
+            }
+        );
+    };
     // Gen statement struct
     {
         let sql = sql.replace('"', "\\\""); // Rust string format escaping
         let name = &ident.rs;
+        let stmt_name = format!("{}.{}", module.info.name, ident.db);
+        let attrs: Vec<_> = attributes.item_attrs().collect();
+        let attrs = attrs.iter();
         code!(w =>
-            pub fn $name() -> ${struct_name}Stmt {
-                ${struct_name}Stmt($client::private::Stmt::new("$sql"))
+            $(#[$attrs])
+            $vis fn $name() -> ${struct_name}Stmt {
+                ${struct_name}Stmt($client::private::Stmt::named("$sql", "$stmt_name"))
             }
-            pub struct ${struct_name}Stmt($client::private::Stmt);
+            $(#[$cfg])
+            $vis struct ${struct_name}Stmt($client::private::Stmt);
+            $(#[$cfg])
             impl ${struct_name}Stmt {
                 $!lazy_impl
             }
         );
     }
 
+    // Gen typestate builder, only finished once every non-nullable param is set
+    if gen_builder && !order.is_empty() {
+        let traits = &mut Vec::new();
+        let fields: Vec<_> = order
+            .iter()
+            .map(|idx| {
+                let field = &param_field[*idx];
+                let start = traits.len();
+                let ty = field.param_ergo_ty(traits, ctx);
+                let generics: Vec<_> = (start..traits.len())
+                    .map(|i| format!("{}: {}", idx_char(i + 1), traits[i]))
+                    .collect();
+                (field, ty, generics)
+            })
+            .collect();
+        // One state per param, either its unset default or the borrowed value
+        let states: Vec<_> = (1..=fields.len()).map(|i| format!("P{i}")).collect();
+        let unset_states = fields.iter().map(|(field, _, _)| {
+            if field.is_nullable {
+                format!("&'a {}", field.param_ty(ctx))
+            } else {
+                "()".to_string()
+            }
+        });
+        // Nullable params default to `None`
+        let unset_values = fields.iter().map(|(field, _, _)| {
+            if field.is_nullable {
+                let ty = field.ty.param_ty(field.is_inner_nullable, ctx);
+                format!("&None::<{ty}>")
+            } else {
+                "()".to_string()
+            }
+        });
+        let setters = |w: &mut String| {
+            for (i, (field, ty, generics)) in fields.iter().enumerate() {
+                let name = &field.ident.rs;
+                // Keep the finishing method reachable
+                let setter = if name == "execute" { "execute_" } else { name };
+                let generics = generics.iter();
+                let set_states = states.iter().enumerate().map(|(j, it)| {
+                    if i == j {
+                        format!("&'a {ty}")
+                    } else {
+                        it.clone()
+                    }
+                });
+                let values = (1..=fields.len()).map(|j| {
+                    if j == i + 1 {
+                        name.clone()
+                    } else {
+                        format!("self.{j}")
+                    }
+                });
+                code!(w =>
+                    pub fn $setter<$($generics,)>(self, $name: &'a $ty) -> ${struct_name}Builder<'a, $($set_states,)> {
+                        ${struct_name}Builder(self.0, $($values,))
+                    }
+                );
+            }
+        };
+        let states = states.iter();
+        let set_states = fields.iter().map(|(_, ty, _)| format!("&'a {ty}"));
+        let values = (1..=fields.len()).map(|i| format!("self.{i}"));
+        let fn_async = if is_builder { "" } else { fn_async };
+        let fn_await = if is_builder { "" } else { fn_await };
+        code!(w =>
+            $(#[$cfg])
+            $vis struct ${struct_name}Builder<'a, $($states,)>(&'a mut ${struct_name}Stmt, $($states,));
+            $(#[$cfg])
+            impl ${struct_name}Stmt {
+                pub fn builder<'a>(&'a mut self) -> ${struct_name}Builder<'a, $($unset_states,)> {
+                    ${struct_name}Builder(self, $($unset_values,))
+                }
+            }
+            $(#[$cfg])
+            impl<'a, $($states,)> ${struct_name}Builder<'a, $($states,)> {
+                $!setters
+            }
+            $(#[$cfg])
+            impl<'a, $($traits_idx: $traits,)> ${struct_name}Builder<'a, $($set_states,)> {
+                pub $fn_async fn execute<C: GenericClient>(self, client: &'a $client_mut C) -> $bind_ret {
+                    self.0.bind(client, $($values,))$fn_await
+                }
+            }
+        );
+    }
+
     // Param impl
     if let Some(param) = param {
         if param.is_named {
@@ -595,49 +1047,214 @@ fn gen_query_fn<W: Write>(w: &mut W, module: &PreparedModule, query: &PreparedQu
             } else {
                 "'a,"
             };
-            if let Some((idx, _)) = row {
-                let prepared_row = &module.rows.get_index(*idx).unwrap().1;
-                let query_row_struct = if prepared_row.is_named {
-                    prepared_row.path(ctx)
+            // Async fn are boxed to be returned from the trait
+            let (send_sync, out_ty) = if ctx.is_async && !is_builder {
+                (
+                    "+ Send + Sync",
+                    format!(
+                        "std::pin::Pin<Box<dyn futures::Future<Output = {bind_ret}> + Send + 'a>>"
+                    ),
+                )
+            } else {
+                ("", bind_ret.clone())
+            };
+            let (pre, post) = if ctx.is_async && !is_builder {
+                ("Box::pin(", ")")
+            } else {
+                ("", "")
+            };
+            let cfg_owned = cfg.clone();
+            let owned_body = |w: &mut String| {
+                let values: Vec<_> = order
+                    .iter()
+                    .map(|idx| format!("&params.{}", param_field[*idx].ident.rs))
+                    .collect();
+                let body = |w: &mut String| gen_body(w, "self.0", &values);
+                if ctx.is_async && !is_builder {
+                    code!(w => Box::pin(async move { $!body }))
                 } else {
-                    prepared_row.fields[0].own_struct(ctx)
-                };
-                let name = &module.rows.get_index(*idx).unwrap().1.name;
-                let nb_params = param_field.len();
-                code!(w =>
-                    impl <'a, C: GenericClient,$($traits_idx: $traits,)> $client::Params<'a, $param_path<$lifetime $($traits_idx,)>, ${name}Query<'a, C, $query_row_struct, $nb_params>, C> for ${struct_name}Stmt {
-                        fn params(&'a mut self, client: &'a $client_mut C, params: &'a $param_path<$lifetime $($traits_idx,)>) -> ${name}Query<'a, C, $query_row_struct, $nb_params> {
-                            self.bind(client, $(&params.$params_name,))
-                        }
+                    code!(w => $!body)
+                }
+            };
+            code!(w =>
+                $(#[$cfg])
+                impl <'a, C: GenericClient $send_sync, $($traits_idx: $traits,)> $client::Params<'a, $param_path<$lifetime $($traits_idx,)>, $out_ty, C> for ${struct_name}Stmt {
+                    fn params(&'a mut self, client: &'a $client_mut C, params: &'a $param_path<$lifetime $($traits_idx,)>) -> $out_ty {
+                        ${pre}self.bind(client, $(&params.$params_name,))$post
                     }
-                );
-            } else {
-                let (send_sync, pre_ty, post_ty_lf, pre, post) = if ctx.is_async {
-                    (
-                        "+ Send + Sync",
-                        "std::pin::Pin<Box<dyn futures::Future<Output = Result",
-                        "> + Send + 'a>>",
-                        "Box::pin(self",
-                        ")",
-                    )
+                }
+                $(#[$cfg_owned])
+                impl <'a, C: GenericClient $send_sync> $client::Params<'a, ${param_path}Owned, $out_ty, C> for ${struct_name}Stmt {
+                    fn params(&'a mut self, client: &'a $client_mut C, params: &'a ${param_path}Owned) -> $out_ty {
+                        $!owned_body
+                    }
+                }
+            );
+        }
+    }
+
+    // Repository method, binding owned values with the statement cached by the repository
+    gen_repository.then(|| {
+        let name = &ident.rs;
+        let args = order.iter().map(|idx| {
+            let field = &param_field[*idx];
+            format!("{}: {}", field.ident.rs, field.own_struct(ctx))
+        });
+        let ret = match row_item {
+            Some(item) => {
+                let row_struct_name = if item.is_named {
+                    item.path(ctx)
                 } else {
-                    ("", "Result", "", "self", "")
+                    item.fields[0].own_struct(ctx)
                 };
-                code!(w =>
-                    impl <'a, C: GenericClient $send_sync, $($traits_idx: $traits,)> $client::Params<'a, $param_path<$lifetime $($traits_idx,)>, $pre_ty<u64, $backend::Error>$post_ty_lf, C> for ${struct_name}Stmt {
-                        fn params(&'a mut self, client: &'a $client_mut C, params: &'a $param_path<$lifetime $($traits_idx,)>) -> $pre_ty<u64, $backend::Error>$post_ty_lf {
-                            $pre.bind(client, $(&params.$params_name,))$post
-                        }
-                    }
-                );
+                direct_ret
+                    .clone()
+                    .unwrap_or_else(|| format!("Vec<{row_struct_name}>"))
             }
+            None => String::from("u64"),
+        };
+        let values: Vec<_> = order
+            .iter()
+            .map(|idx| format!("&{}", param_field[*idx].ident.rs))
+            .collect();
+        let stmt = format!("self.stmts.{name}.0");
+        let client_ref = if ctx.is_async {
+            "self.client"
+        } else {
+            "&mut *self.client"
+        };
+        let query = |w: &mut String| gen_body(w, &stmt, &values);
+        let mut body = String::new();
+        let w = &mut body;
+        // Errors are converted into the error type of the repository
+        if is_builder {
+            code!(w =>
+                let client = $client_ref;
+                let query = $!query;
+                let result: Result<_, $error> = query.all()$fn_await;
+                result.map_err(Into::into)
+            );
+        } else {
+            code!(w =>
+                let client = $client_ref;
+                let result: Result<_, $error> = { $!query };
+                result.map_err(Into::into)
+            );
         }
+        RepositoryMethod {
+            name: name.clone(),
+            is_crate_visible: vis == "pub(crate)",
+            is_expect: expect.is_some(),
+            sig: code!($fn_async fn $name(&mut self, $($args,)) -> Result<$ret, Self::Error>),
+            body,
+            stmt: format!("{struct_name}Stmt"),
+            cfg: attributes.cfg_attrs().map(|it| it.to_string()).collect(),
+            is_deprecated: attributes.is_deprecated(),
+        }
+    })
+}
+
+/// Generates the repository trait of a module, describing its queries with owned inputs and
+/// outputs so they can be mocked, and its implementation by a client caching their statements.
+fn gen_repository(w: &mut String, methods: &[RepositoryMethod], ctx: &GenCtx) {
+    if methods.is_empty() {
+        return;
     }
+    // The repository must not be more visible than the items its methods expose
+    let vis = if methods.iter().any(|it| it.is_crate_visible) {
+        "pub(crate)"
+    } else {
+        "pub"
+    };
+    let (client_mut, async_trait, backend, client) = if ctx.is_async {
+        (
+            "",
+            "#[cornucopia_async::private::async_trait]",
+            "tokio_postgres",
+            "cornucopia_async",
+        )
+    } else {
+        ("mut", "", "postgres", "cornucopia_sync")
+    };
+    // Row count errors also hold database errors
+    let error = if methods.iter().any(|it| it.is_expect) {
+        format!("{client}::RowCountError")
+    } else {
+        format!("{backend}::Error")
+    };
+    let cfg = methods.iter().map(|it| {
+        let cfg = it.cfg.iter();
+        code!($(#[$cfg]))
+    });
+    let sig = methods.iter().map(|it| &it.sig);
+    let body = methods.iter().map(|it| &it.body);
+    let name = methods.iter().map(|it| &it.name);
+    let stmt_fn = name.clone();
+    // Only the statements of deprecated queries may use them
+    let stmt_cfg = methods.iter().map(|it| {
+        let cfg = it.cfg.iter();
+        let allow = if it.is_deprecated {
+            "#[allow(deprecated)]"
+        } else {
+            ""
+        };
+        code!($(#[$cfg]) $allow)
+    });
+    let stmt = methods.iter().map(|it| &it.stmt);
+    code!(w =>
+        $async_trait
+        $vis trait Repository {
+            type Error;
+            $($cfg $sig;)
+        }
+        struct RepositoryStmts {
+            $($cfg $name: $stmt,)
+        }
+        $vis struct ClientRepository<'a, C: GenericClient> {
+            client: &'a $client_mut C,
+            stmts: RepositoryStmts,
+        }
+        impl<'a, C: GenericClient> ClientRepository<'a, C> {
+            pub fn new(client: &'a $client_mut C) -> Self {
+                Self {
+                    client,
+                    stmts: RepositoryStmts {
+                        $($stmt_cfg $name: $stmt_fn(),)
+                    },
+                }
+            }
+        }
+        $async_trait
+        impl<'a, C: GenericClient> Repository for ClientRepository<'a, C> {
+            type Error = $error;
+            $($cfg $sig {
+                $body
+            })
+        }
+    );
+}
+
+/// Method of a module repository, implemented using a cached statement
+struct RepositoryMethod {
+    name: String,
+    is_crate_visible: bool,
+    is_expect: bool,
+    sig: String,
+    body: String,
+    stmt: String,
+    cfg: Vec<String>,
+    is_deprecated: bool,
 }
 
 /// Generates type definitions for custom user types. This includes domains, composites and enums.
 /// If the type is not `Copy`, then a Borrowed version will be generated.
-fn gen_custom_type(w: &mut impl Write, schema: &str, prepared: &PreparedType, ctx: &GenCtx) {
+fn gen_custom_type(
+    w: &mut impl Write,
+    schema: &str,
+    prepared: &PreparedType,
+    ctx: &GenCtx,
+    derive_ctx: &DeriveCtx,
+) {
     let PreparedType {
         struct_name,
         content,
@@ -645,17 +1262,26 @@ fn gen_custom_type(w: &mut impl Write, schema: &str, prepared: &PreparedType, ct
         is_params,
         name,
     } = prepared;
-    let copy = if *is_copy { "Copy," } else { "" };
+    let copy = if *is_copy { "Copy" } else { "" };
     let ser_str = if ctx.gen_derive {
-        "serde::Serialize,"
+        "serde::Serialize"
     } else {
         ""
     };
+    let configured = derive_ctx.custom_derives(prepared);
     match content {
         PreparedContent::Enum(variants) => {
             let variants_ident = variants.iter().map(|v| &v.rs);
+            let derives = derives(
+                &[
+                    &[ser_str, "Debug", "Clone", "Copy", "PartialEq", "Eq"],
+                    configured.as_slice(),
+                ]
+                .concat(),
+                &Attributes::default(),
+            );
             code!(w =>
-                #[derive($ser_str Debug, Clone, Copy, PartialEq, Eq)]
+                #[derive($derives)]
                 #[allow(non_camel_case_types)]
                 pub enum $struct_name {
                     $($variants_ident,)
@@ -668,8 +1294,23 @@ fn gen_custom_type(w: &mut impl Write, schema: &str, prepared: &PreparedType, ct
             let fields_name = fields.iter().map(|p| &p.ident.rs);
             {
                 let fields_ty = fields.iter().map(|p| p.own_struct(ctx));
+                let derives = derives(
+                    &[
+                        &[
+                            ser_str,
+                            "Debug",
+                            "postgres_types::FromSql",
+                            copy,
+                            "Clone",
+                            "PartialEq",
+                        ],
+                        configured.as_slice(),
+                    ]
+                    .concat(),
+                    &Attributes::default(),
+                );
                 code!(w =>
-                    #[derive($ser_str Debug,postgres_types::FromSql,$copy Clone, PartialEq)]
+                    #[derive($derives)]
                     #[postgres(name = "$name")]
                     pub struct $struct_name {
                         $(
@@ -713,6 +1354,8 @@ fn gen_custom_type(w: &mut impl Write, schema: &str, prepared: &PreparedType, ct
                     );
                 }
                 struct_tosql(w, struct_name, fields, name, true, *is_params, ctx);
+                // Owned composites are bound by owned params
+                struct_tosql(w, struct_name, fields, name, false, *is_params, ctx);
             }
         }
     }
@@ -722,12 +1365,13 @@ fn gen_type_modules<W: Write>(
     w: &mut W,
     prepared: &IndexMap<String, Vec<PreparedType>>,
     ctx: &GenCtx,
+    derive_ctx: &DeriveCtx,
 ) {
     let modules = prepared.iter().map(|(schema, types)| {
         move |w: &mut W| {
             let lazy = |w: &mut W| {
                 for ty in types {
-                    gen_custom_type(w, schema, ty, ctx)
+                    gen_custom_type(w, schema, ty, ctx, derive_ctx)
                 }
             };
 
@@ -751,25 +1395,35 @@ fn gen_type_modules<W: Write>(
 pub(crate) fn generate(preparation: Preparation, settings: CodegenSettings) -> String {
     let mut buff = "// This file was generated with `cornucopia`. Do not modify.\n\n".to_string();
     let w = &mut buff;
+    let settings = &settings;
+    let derive_ctx = &DeriveCtx {
+        derives: &settings.derives,
+        derive_ser: settings.derive_ser,
+        types: &preparation.types,
+    };
     // Generate database type
     gen_type_modules(
         w,
         &preparation.types,
         &GenCtx::new(1, settings.gen_async, settings.derive_ser),
+        derive_ctx,
     );
     // Generate queries
     let query_modules = preparation.modules.iter().map(|module| {
         move |w: &mut String| {
             let name = &module.info.name;
             let ctx = GenCtx::new(2, settings.gen_async, settings.derive_ser);
+            // Shared structs are generated once in the shared module and external ones are user-defined
             let params_string = module
                 .params
                 .values()
-                .map(|params| |w: &mut String| gen_params_struct(w, params,  &ctx));
+                .filter(|params| !params.is_shared)
+                .map(|params| |w: &mut String| gen_params_struct(w, params, &ctx, derive_ctx));
             let rows_struct_string = module
                 .rows
                 .values()
-                .map(|row| |w: &mut String| gen_row_structs(w, row,  &ctx));
+                .filter(|row| !row.is_shared && !row.is_external)
+                .map(|row| |w: &mut String| gen_row_structs(w, row, &ctx, derive_ctx));
 
             let sync_specific = |w: &mut String| {
                 let gen_specific = |depth: u8, is_async: bool| {
@@ -784,13 +1438,27 @@ pub(crate) fn generate(preparation: Preparation, settings: CodegenSettings) -> S
                             .rows
                             .values()
                             .map(|row| |w: &mut String| gen_row_query(w, row, &ctx));
-                        let queries_string = module.queries.values().map(|query| {
-                            |w: &mut String| gen_query_fn(w, module, query, &ctx)
-                        });
+                        let mut queries_string = String::new();
+                        let methods: Vec<_> = module
+                            .queries
+                            .values()
+                            .filter_map(|query| {
+                                gen_query_fn(
+                                    &mut queries_string,
+                                    module,
+                                    query,
+                                    &ctx,
+                                    settings.gen_builder,
+                                    settings.gen_repository,
+                                )
+                            })
+                            .collect();
+                        let repository = |w: &mut String| gen_repository(w, &methods, &ctx);
                         code!(w =>
                             $import
                             $($!rows_query_string)
-                            $($!queries_string)
+                            $queries_string
+                            $!repository
                         )
                     }
                 };
@@ -827,12 +1495,37 @@ pub(crate) fn generate(preparation: Preparation, settings: CodegenSettings) -> S
             );
         }
     });
+    // Generate shared structs
+    let shared_module = |w: &mut String| {
+        if let Some(shared) = preparation
+            .shared
+            .as_ref()
+            .filter(|it| !it.params.is_empty() || !it.rows.is_empty())
+        {
+            let ctx = GenCtx::new(2, settings.gen_async, settings.derive_ser);
+            let params_string = shared
+                .params
+                .values()
+                .map(|params| |w: &mut String| gen_params_struct(w, params, &ctx, derive_ctx));
+            let rows_struct_string = shared
+                .rows
+                .values()
+                .map(|row| |w: &mut String| gen_row_structs(w, row, &ctx, derive_ctx));
+            code!(w =>
+                pub mod shared {
+                    $($!params_string)
+                    $($!rows_struct_string)
+                }
+            );
+        }
+    };
     code!(w =>
         #[allow(clippy::all, clippy::pedantic)]
         #[allow(unused_variables)]
         #[allow(unused_imports)]
         #[allow(dead_code)]
         pub mod queries {
+            $!shared_module
             $($!query_modules)
         }
     );
@@ -1,59 +1,375 @@
-use std::process::{Command, Stdio};
+use std::{
+    ffi::OsStr,
+    net::TcpListener,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 use self::error::Error;
 
+/// Engine running Cornucopia's database.
+#[derive(Debug, Clone, Default)]
+pub enum Engine {
+    /// A `docker` container
+    #[default]
+    Docker,
+    /// A `podman` container
+    Podman,
+    /// A throwaway cluster created by the locally installed `initdb` and `pg_ctl` binaries,
+    /// in a temporary directory named after the container.
+    Local {
+        /// Directory containing the binaries, looked up on `PATH` if unset
+        bin_dir: Option<PathBuf>,
+    },
+}
+
+/// Settings of Cornucopia's database container.
+#[derive(Debug, Clone)]
+pub struct ContainerConfig {
+    /// Engine running the database
+    pub engine: Engine,
+    /// Image of the container, including its tag (e.g. `docker.io/library/postgres:15`)
+    pub image: String,
+    /// Host port the container's Postgres port is published on.
+    ///
+    /// If unset, the container engine picks a free port, or a free port is looked up for a
+    /// local cluster, trying another one if it is taken before the cluster starts.
+    pub port: Option<u16>,
+    /// Name of the container, a name unique to this run is generated if unset
+    pub name: Option<String>,
+    /// Password of the `postgres` user
+    pub password: String,
+    /// Additional environment variables, formatted as `KEY=VALUE`
+    pub env: Vec<String>,
+    /// Additional arguments given to the container manager's `run` command,
+    /// or to the server when using a local cluster
+    pub args: Vec<String>,
+    /// Extensions created before loading the schema, they must be available in the image
+    pub extensions: Vec<String>,
+    /// Keep the container running after use and reuse it if it is already running.
+    ///
+    /// Each run creates its own database from a template instead of starting a new
+    /// container, and drops it on cleanup. A kept container whose image, environment, port
+    /// or password don't match the configuration is recreated. Defaults to the
+    /// `cornucopia_postgres` name and the 5435 port, to find the container back between runs.
+    pub keep: bool,
+}
+
+impl Default for ContainerConfig {
+    fn default() -> Self {
+        Self {
+            engine: Engine::Docker,
+            image: String::from("docker.io/library/postgres:latest"),
+            port: None,
+            name: None,
+            password: String::from("postgres"),
+            env: Vec::new(),
+            args: Vec::new(),
+            extensions: Vec::new(),
+            keep: false,
+        }
+    }
+}
+
+/// Database container started by [`setup`].
+///
+/// The container is stopped and removed when dropped, use [`Container::cleanup`]
+/// to handle the errors of its removal. Kept containers are left running.
+#[derive(Debug)]
+pub struct Container {
+    config: ContainerConfig,
+    name: String,
+    port: u16,
+    database: String,
+    spawned: bool,
+}
+
+impl Container {
+    /// Name of the container
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Host the container is reachable on
+    pub fn host(&self) -> &str {
+        "127.0.0.1"
+    }
+
+    /// Host port the container's Postgres port is published on
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Postgres user
+    pub fn user(&self) -> &str {
+        "postgres"
+    }
+
+    /// Password of the Postgres user
+    pub fn password(&self) -> &str {
+        &self.config.password
+    }
+
+    /// Database to load the schema into
+    pub fn database(&self) -> &str {
+        &self.database
+    }
+
+    /// Stop and remove the container and its volume, or only drop the database of this run
+    /// if it is kept.
+    pub fn cleanup(mut self) -> Result<(), Error> {
+        if self.config.keep {
+            self.drop_database()
+        } else {
+            self.remove()
+        }
+    }
+
+    /// Stop and remove the container and its volume, even if it is kept.
+    pub fn remove(mut self) -> Result<(), Error> {
+        self.spawned = false;
+        self.destroy()
+    }
+
+    /// Checks if a kept container is already running.
+    fn is_running(&self) -> Result<bool, Error> {
+        if let Engine::Local { bin_dir } = &self.config.engine {
+            let data = cluster_dir(self).join("data");
+            Ok(data.exists()
+                && run(
+                    self,
+                    local_cmd(self, bin_dir.as_deref(), "pg_ctl")
+                        .args(["status", "-D"])
+                        .arg(data),
+                    "check cluster status",
+                )
+                .is_ok())
+        } else {
+            is_postgres_healthy(self)
+        }
+    }
+
+    /// Starts a kept container that exists but is stopped, returns `false` if it doesn't exist.
+    fn start(&self) -> Result<bool, Error> {
+        if let Engine::Local { bin_dir } = &self.config.engine {
+            if !cluster_dir(self).join("data").exists() {
+                return Ok(false);
+            }
+            start_cluster(self, bin_dir.as_deref())?;
+        } else if cmd(self, &["start", &self.name], "start container").is_ok() {
+            healthcheck(self, 120, 50)?;
+        } else {
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Checks if a kept container was created with the current configuration.
+    fn matches_config(&self) -> Result<bool, Error> {
+        if !matches!(self.config.engine, Engine::Local { .. }) {
+            let inspect = |format: &str| {
+                cmd_output(
+                    self,
+                    &["inspect", "--format", format, &self.name],
+                    "inspect container",
+                )
+            };
+            let image = inspect("{{.Config.Image}}")?;
+            // The image defines its own variables besides the configured ones
+            let env = inspect("{{range .Config.Env}}{{println .}}{{end}}")?;
+            let env: Vec<_> = env.lines().collect();
+            let password = format!("POSTGRES_PASSWORD={}", self.config.password);
+            if image.trim() != self.config.image
+                || !std::iter::once(&password)
+                    .chain(&self.config.env)
+                    .all(|it| env.contains(&it.as_str()))
+                || published_port(self)? != self.port
+            {
+                return Ok(false);
+            }
+        }
+        // Checks the port and password of local clusters, which have no recorded configuration
+        Ok(self.connect().is_ok())
+    }
+
+    /// Connects to the `postgres` database of the container.
+    fn connect(&self) -> Result<postgres::Client, postgres::Error> {
+        crate::conn::container_config(self)
+            .dbname("postgres")
+            .connect(postgres::NoTls)
+    }
+
+    /// Creates a database for this run from a template, faster than starting a new
+    /// container and not shared with concurrent runs.
+    fn reset(&mut self) -> Result<(), Error> {
+        let database = unique_name("cornucopia");
+        self.connect()
+            .and_then(|mut client| {
+                client.batch_execute(&format!("CREATE DATABASE {database} TEMPLATE template0"))
+            })
+            .map_err(|err| Error::database("create", &err))?;
+        self.database = database;
+        Ok(())
+    }
+
+    /// Drops the database of this run from a kept container.
+    fn drop_database(&mut self) -> Result<(), Error> {
+        let database = std::mem::replace(&mut self.database, String::from("postgres"));
+        if database == "postgres" {
+            return Ok(());
+        }
+        self.connect()
+            .and_then(|mut client| {
+                client.batch_execute(&format!("DROP DATABASE IF EXISTS {database}"))
+            })
+            .map_err(|err| Error::database("drop", &err))
+    }
+
+    fn destroy(&self) -> Result<(), Error> {
+        if let Engine::Local { bin_dir } = &self.config.engine {
+            remove_cluster(self, bin_dir.as_deref())
+        } else {
+            stop_container(self)?;
+            remove_container(self)
+        }
+    }
+}
+
+impl Drop for Container {
+    fn drop(&mut self) {
+        if self.config.keep {
+            self.drop_database().ok();
+        } else if self.spawned {
+            self.destroy().ok();
+        }
+    }
+}
+
 /// Starts Cornucopia's database container and wait until it reports healthy.
-pub fn setup(podman: bool) -> Result<(), Error> {
-    spawn_container(podman)?;
-    healthcheck(podman, 120, 50)?;
-    Ok(())
+///
+/// A kept container that already exists is reused, and started again if it is stopped,
+/// with a fresh database. It is recreated if it doesn't match the configuration.
+pub fn setup(config: &ContainerConfig) -> Result<Container, Error> {
+    let name = match &config.name {
+        Some(name) => name.clone(),
+        None if config.keep => String::from("cornucopia_postgres"),
+        None => unique_name("cornucopia_postgres"),
+    };
+    // Containers are published on a port picked by their engine when none is configured
+    let port = match config.port {
+        Some(port) => port,
+        None if config.keep => 5435,
+        None if matches!(config.engine, Engine::Local { .. }) => free_port()?,
+        None => 0,
+    };
+    let mut container = Container {
+        config: config.clone(),
+        name,
+        port,
+        database: String::from("postgres"),
+        spawned: false,
+    };
+    let mut reused = config.keep && (container.is_running()? || container.start()?);
+    if reused && !container.matches_config()? {
+        container.destroy()?;
+        reused = false;
+    }
+    if !reused {
+        if let Engine::Local { bin_dir } = &config.engine {
+            spawn_cluster(&mut container, bin_dir.as_deref())?;
+        } else {
+            spawn_container(&container)?;
+            container.spawned = true;
+            if container.port == 0 {
+                container.port = published_port(&container)?;
+            }
+            healthcheck(&container, 120, 50)?;
+        }
+    }
+    if config.keep {
+        container.reset()?;
+    }
+    Ok(container)
 }
 
-/// Stop and remove a container and its volume.
-pub fn cleanup(podman: bool) -> Result<(), Error> {
-    stop_container(podman)?;
-    remove_container(podman)?;
-    Ok(())
+/// Generates a name unique to this run.
+fn unique_name(prefix: &str) -> String {
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+    let count = COUNT.fetch_add(1, Ordering::Relaxed);
+    format!("{prefix}_{}_{count}", std::process::id())
+}
+
+/// Finds a port that is currently free on the host, another process may take it before use.
+fn free_port() -> Result<u16, Error> {
+    Ok(TcpListener::bind("127.0.0.1:0")?.local_addr()?.port())
 }
 
 /// Starts Cornucopia's database container.
-fn spawn_container(podman: bool) -> Result<(), Error> {
-    cmd(
-        podman,
-        &[
-            "run",
-            "-d",
-            "--name",
-            "cornucopia_postgres",
-            "-p",
-            "5435:5432",
-            "-e",
-            "POSTGRES_PASSWORD=postgres",
-            "docker.io/library/postgres:latest",
-        ],
-        "spawn container",
-    )
+fn spawn_container(container: &Container) -> Result<(), Error> {
+    let config = &container.config;
+    let port = if container.port == 0 {
+        String::from("127.0.0.1::5432")
+    } else {
+        format!("{}:5432", container.port)
+    };
+    let password = format!("POSTGRES_PASSWORD={}", config.password);
+    let mut args = vec![
+        "run",
+        "-d",
+        "--name",
+        &container.name,
+        "-p",
+        &port,
+        "-e",
+        &password,
+    ];
+    for env in &config.env {
+        args.extend(["-e", env]);
+    }
+    args.extend(config.args.iter().map(String::as_str));
+    args.push(&config.image);
+    cmd(container, &args, "spawn container")
+}
+
+/// Finds the host port the engine published the container's Postgres port on.
+fn published_port(container: &Container) -> Result<u16, Error> {
+    let output = cmd_output(
+        container,
+        &["port", &container.name, "5432/tcp"],
+        "find container port",
+    )?;
+    // Formatted as `host:port`, one line per published address
+    output
+        .lines()
+        .find_map(|line| line.trim().rsplit_once(':')?.1.parse().ok())
+        .ok_or_else(|| {
+            Error::new(
+                format!("Couldn't parse the published port of the container: `{output}`"),
+                container,
+            )
+        })
 }
 
 /// Checks if Cornucopia's container reports healthy
-fn is_postgres_healthy(podman: bool) -> Result<bool, Error> {
+fn is_postgres_healthy(container: &Container) -> Result<bool, Error> {
     Ok(cmd(
-        podman,
-        &["exec", "cornucopia_postgres", "pg_isready"],
+        container,
+        &["exec", &container.name, "pg_isready"],
         "check container health",
     )
     .is_ok())
 }
 
 /// This function controls how the healthcheck retries are handled.
-fn healthcheck(podman: bool, max_retries: u64, ms_per_retry: u64) -> Result<(), Error> {
+fn healthcheck(container: &Container, max_retries: u64, ms_per_retry: u64) -> Result<(), Error> {
     let slow_threshold = 10 + max_retries / 10;
     let mut nb_retries = 0;
-    while !is_postgres_healthy(podman)? {
+    while !is_postgres_healthy(container)? {
         if nb_retries >= max_retries {
             return Err(Error::new(
                 String::from("Cornucopia reached the max number of connection retries"),
-                podman,
+                container,
             ));
         };
         std::thread::sleep(std::time::Duration::from_millis(ms_per_retry));
@@ -69,34 +385,183 @@ fn healthcheck(podman: bool, max_retries: u64, ms_per_retry: u64) -> Result<(),
 }
 
 /// Stops Cornucopia's container.
-fn stop_container(podman: bool) -> Result<(), Error> {
-    cmd(podman, &["stop", "cornucopia_postgres"], "stop container")
+fn stop_container(container: &Container) -> Result<(), Error> {
+    cmd(container, &["stop", &container.name], "stop container")
 }
 
 /// Removes Cornucopia's container and its volume.
-fn remove_container(podman: bool) -> Result<(), Error> {
+fn remove_container(container: &Container) -> Result<(), Error> {
     cmd(
-        podman,
-        &["rm", "-v", "cornucopia_postgres"],
+        container,
+        &["rm", "-v", &container.name],
         "remove container",
     )
 }
 
-fn cmd(podman: bool, args: &[&'static str], action: &'static str) -> Result<(), Error> {
-    let command = if podman { "podman" } else { "docker" };
-    let output = Command::new(command)
-        .args(args)
+fn cmd(container: &Container, args: &[&str], action: &'static str) -> Result<(), Error> {
+    run(container, engine_cmd(container).args(args), action)
+}
+
+fn cmd_output(container: &Container, args: &[&str], action: &'static str) -> Result<String, Error> {
+    output(container, engine_cmd(container).args(args), action)
+}
+
+fn engine_cmd(container: &Container) -> Command {
+    if let Engine::Podman = container.config.engine {
+        Command::new("podman")
+    } else {
+        Command::new("docker")
+    }
+}
+
+/// Directory of the local cluster
+fn cluster_dir(container: &Container) -> PathBuf {
+    std::env::temp_dir().join(&container.name)
+}
+
+/// Creates and starts a local cluster, waiting until it accepts connections.
+fn spawn_cluster(container: &mut Container, bin_dir: Option<&Path>) -> Result<(), Error> {
+    let dir = cluster_dir(container);
+    match create_private_dir(&dir) {
+        Ok(()) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+            return Err(Error::new(
+                format!("Cluster directory `{}` already exists", dir.display()),
+                container,
+            ))
+        }
+        Err(err) => return Err(err.into()),
+    }
+    container.spawned = true;
+    let pwfile = dir.join("pwfile");
+    std::fs::write(&pwfile, &container.config.password)?;
+    let data = dir.join("data");
+    let created = run(
+        container,
+        local_cmd(container, bin_dir, "initdb")
+            .args(["-U", "postgres", "-A", "scram-sha-256", "-E", "UTF8", "-D"])
+            .arg(&data)
+            .arg("--pwfile")
+            .arg(&pwfile),
+        "create cluster",
+    );
+    // The password is only needed by `initdb`
+    std::fs::remove_file(&pwfile)?;
+    created?;
+    // The free port may be taken before the cluster starts, another one is tried then
+    let mut attempts = if container.config.port.is_none() && !container.config.keep {
+        3
+    } else {
+        1
+    };
+    loop {
+        attempts -= 1;
+        match start_cluster(container, bin_dir) {
+            Err(_) if attempts > 0 => container.port = free_port()?,
+            result => return result,
+        }
+    }
+}
+
+/// Creates a directory only readable by the current user, failing if it already exists
+/// to not share it with another run or user.
+#[cfg(unix)]
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+    std::fs::DirBuilder::new().mode(0o700).create(dir)
+}
+
+#[cfg(not(unix))]
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir(dir)
+}
+
+/// Starts an existing local cluster, waiting until it accepts connections.
+fn start_cluster(container: &Container, bin_dir: Option<&Path>) -> Result<(), Error> {
+    let dir = cluster_dir(container);
+    let mut options = format!(
+        "-p {} -c listen_addresses=127.0.0.1 -k \"{}\"",
+        container.port,
+        dir.display()
+    );
+    for arg in &container.config.args {
+        options.push(' ');
+        options.push_str(arg);
+    }
+    run(
+        container,
+        local_cmd(container, bin_dir, "pg_ctl")
+            .args(["start", "-w", "-D"])
+            .arg(dir.join("data"))
+            .arg("-l")
+            .arg(dir.join("log"))
+            .args(["-o", &options]),
+        "start cluster",
+    )
+}
+
+/// Stops a local cluster and removes its directory.
+fn remove_cluster(container: &Container, bin_dir: Option<&Path>) -> Result<(), Error> {
+    let dir = cluster_dir(container);
+    if !dir.exists() {
+        return Err(Error::new(
+            format!("Cluster directory `{}` doesn't exist", dir.display()),
+            container,
+        ));
+    }
+    let data = dir.join("data");
+    if data.join("postmaster.pid").exists() {
+        run(
+            container,
+            local_cmd(container, bin_dir, "pg_ctl")
+                .args(["stop", "-w", "-m", "immediate", "-D"])
+                .arg(&data),
+            "stop cluster",
+        )?;
+    }
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+fn local_cmd(container: &Container, bin_dir: Option<&Path>, bin: &str) -> Command {
+    let mut command = match bin_dir {
+        Some(dir) => Command::new(dir.join(bin)),
+        None => Command::new(bin),
+    };
+    command.envs(
+        container
+            .config
+            .env
+            .iter()
+            .filter_map(|env| env.split_once('='))
+            .map(|(key, value)| (OsStr::new(key), OsStr::new(value))),
+    );
+    command
+}
+
+fn run(container: &Container, command: &mut Command, action: &'static str) -> Result<(), Error> {
+    output(container, command.stdout(Stdio::null()), action).map(|_| ())
+}
+
+/// Runs a command, returning its standard output.
+fn output(
+    container: &Container,
+    command: &mut Command,
+    action: &'static str,
+) -> Result<String, Error> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let output = command
         .stderr(Stdio::piped())
-        .stdout(Stdio::null())
-        .output()?;
+        .output()
+        .map_err(|err| Error::new(format!("`{program}` couldn't {action}: {err}"), container))?;
 
     if output.status.success() {
-        Ok(())
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
     } else {
         let err = String::from_utf8_lossy(&output.stderr);
         Err(Error::new(
-            format!("`{command}` couldn't {action}: {err}"),
-            podman,
+            format!("`{program}` couldn't {action}: {err}"),
+            container,
         ))
     }
 }
@@ -107,6 +572,8 @@ pub(crate) mod error {
     use miette::Diagnostic;
     use thiserror::Error as ThisError;
 
+    use super::{Container, Engine};
+
     #[derive(Debug, ThisError, Diagnostic)]
     #[error("{msg}")]
     pub struct Error {
@@ -116,15 +583,27 @@ pub(crate) mod error {
     }
 
     impl Error {
-        pub fn new(msg: String, podman: bool) -> Self {
-            let help = if podman {
-                "Make sure that port 5435 is usable and that no container named `cornucopia_postgres` already exists."
-            } else {
-                "First, check that the docker daemon is up-and-running. Then, make sure that port 5435 is usable and that no container named `cornucopia_postgres` already exists."
+        pub fn new(msg: String, container: &Container) -> Self {
+            let Container { port, name, .. } = container;
+            let help = match container.config.engine {
+                Engine::Docker => format!("First, check that the docker daemon is up-and-running. Then, make sure that port {port} is usable and that no container named `{name}` already exists."),
+                Engine::Podman => format!("Make sure that port {port} is usable and that no container named `{name}` already exists."),
+                Engine::Local { .. } => format!("First, check that `initdb` and `pg_ctl` are installed and that you are not running as root. Then, make sure that port {port} is usable and that no cluster named `{name}` already exists."),
             };
             Error {
                 msg,
-                help: Some(String::from(help)),
+                help: Some(help),
+            }
+        }
+    }
+
+    impl Error {
+        pub fn database(action: &str, err: &postgres::Error) -> Self {
+            Error {
+                msg: format!("Couldn't {action} the kept container's database: {err:#}"),
+                help: Some(String::from(
+                    "remove the kept container to start a new one on the next run",
+                )),
             }
         }
     }
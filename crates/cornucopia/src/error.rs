@@ -14,6 +14,8 @@ pub enum Error {
     ReadQueries(#[from] crate::read_queries::error::Error),
     /// An error while trying to parse PostgreSQL query files.
     ParseQueries(#[from] crate::parser::error::Error),
+    /// An error while trying to include fragments into PostgreSQL queries.
+    IncludeFragments(#[from] Box<crate::parser::error::FragmentError>),
     /// An error while trying to validate PostgreSQL query files.
     ValidateQueries(#[from] Box<crate::validation::error::Error>),
     /// An error while manipulating a container managed by Cornucopia.
@@ -22,6 +24,8 @@ pub enum Error {
     PrepareQueries(#[from] crate::prepare_queries::error::Error),
     /// An error while reading PostgreSQL schema files.
     LoadSchema(#[from] crate::load_schema::error::Error),
+    /// An error while copying the schema of a live database.
+    CloneSchema(#[from] crate::clone_schema::error::Error),
     /// An error while trying to write the generated code to its destination file.
     WriteCodeGenFile(#[from] WriteOutputError),
 }
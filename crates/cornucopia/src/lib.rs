@@ -1,4 +1,5 @@
 mod cli;
+mod clone_schema;
 mod codegen;
 mod error;
 mod load_schema;
@@ -20,22 +21,43 @@ use postgres::Client;
 
 use codegen::generate as generate_internal;
 use error::WriteOutputError;
-use parser::parse_query_module;
+use parser::{include_fragments, parse_query_module, Module};
 use prepare_queries::prepare;
 use read_queries::read_query_modules;
 
 #[doc(hidden)]
 pub use cli::run;
 
+pub use clone_schema::{clone_schema, CloneTarget};
+pub use container::{Container, ContainerConfig, Engine};
 pub use error::Error;
-pub use load_schema::load_schema;
+pub use load_schema::{load_extensions, load_migrations, load_schema};
 
 /// Struct containing the settings for code generation.
-#[derive(Clone, Copy)]
+#[derive(Clone, Default)]
 pub struct CodegenSettings {
     pub gen_async: bool,
     pub gen_sync: bool,
     pub derive_ser: bool,
+    pub derives: Derives,
+    pub gen_builder: bool,
+    pub gen_repository: bool,
+}
+
+/// Additional traits to derive on each kind of generated item.
+///
+/// A trait is only derived on an item if the types of all its fields implement it,
+/// for example `Eq` is skipped on a row containing a `f64`.
+#[derive(Clone, Debug, Default)]
+pub struct Derives {
+    /// Owned row structs
+    pub rows: Vec<String>,
+    /// Params structs
+    pub params: Vec<String>,
+    /// Enums
+    pub enums: Vec<String>,
+    /// Owned composite structs
+    pub composites: Vec<String>,
 }
 
 /// Generates Rust queries from PostgreSQL queries located at `queries_path`,
@@ -49,10 +71,7 @@ pub fn generate_live<P: AsRef<Path>>(
     settings: CodegenSettings,
 ) -> Result<String, Error> {
     // Read
-    let modules = read_query_modules(queries_path.as_ref())?
-        .into_iter()
-        .map(parse_query_module)
-        .collect::<Result<_, parser::error::Error>>()?;
+    let modules = read_modules(queries_path.as_ref())?;
     // Generate
     let prepared_modules = prepare(client, modules)?;
     let generated_code = generate_internal(prepared_modules, settings);
@@ -65,30 +84,29 @@ pub fn generate_live<P: AsRef<Path>>(
 }
 
 /// Generates Rust queries from PostgreSQL queries located at `queries_path`, using
-/// a container managed by cornucopia. The database schema is created using `schema_files`.
+/// a container managed by cornucopia. The database schema is created using `schema_files`,
+/// which can also be folders of migrations.
 /// If some `destination` is given, the generated code will be written at that path.
 /// Code generation settings are set using the `settings` parameter.
 ///
-/// By default, the container manager is Docker, but Podman can be used by setting the
-/// `podman` parameter to `true`.
+/// The container's image, port, name and engine are set using the `config`
+/// parameter, as well as the extensions created before loading the schema.
 pub fn generate_managed<P: AsRef<Path>>(
     queries_path: P,
     schema_files: &[P],
     destination: Option<P>,
-    podman: bool,
+    config: &ContainerConfig,
     settings: CodegenSettings,
 ) -> Result<String, Error> {
     // Read
-    let modules = read_query_modules(queries_path.as_ref())?
-        .into_iter()
-        .map(parse_query_module)
-        .collect::<Result<_, parser::error::Error>>()?;
-    container::setup(podman)?;
-    let mut client = conn::cornucopia_conn()?;
+    let modules = read_modules(queries_path.as_ref())?;
+    let container = container::setup(config)?;
+    let mut client = conn::cornucopia_conn(&container)?;
+    load_extensions(&mut client, &config.extensions)?;
     load_schema(&mut client, schema_files)?;
     let prepared_modules = prepare(&mut client, modules)?;
     let generated_code = generate_internal(prepared_modules, settings);
-    container::cleanup(podman)?;
+    container.cleanup()?;
 
     if let Some(destination) = destination {
         write_generated_code(destination.as_ref(), &generated_code)?;
@@ -97,6 +115,56 @@ pub fn generate_managed<P: AsRef<Path>>(
     Ok(generated_code)
 }
 
+/// Generates Rust queries from PostgreSQL queries located at `queries_path`, using
+/// a schema-only copy of the live database at `url`, so that it is only read from.
+/// If some `destination` is given, the generated code will be written at that path.
+/// Code generation settings are set using the `settings` parameter.
+///
+/// The schema is copied using `pg_dump` into the database chosen by `target`.
+pub fn generate_cloned<P: AsRef<Path>>(
+    queries_path: P,
+    url: &str,
+    destination: Option<P>,
+    target: CloneTarget,
+    settings: CodegenSettings,
+) -> Result<String, Error> {
+    // Read
+    let modules = read_modules(queries_path.as_ref())?;
+    let prepared_modules = match target {
+        CloneTarget::Container(config) => {
+            let container = container::setup(config)?;
+            let mut client = conn::cornucopia_conn(&container)?;
+            load_extensions(&mut client, &config.extensions)?;
+            clone_schema(&mut client, url)?;
+            let prepared_modules = prepare(&mut client, modules)?;
+            container.cleanup()?;
+            prepared_modules
+        }
+        CloneTarget::Scratch(scratch_url) => {
+            clone_schema::with_scratch_db(scratch_url, |client| {
+                clone_schema(client, url)?;
+                Ok(prepare(client, modules)?)
+            })?
+        }
+    };
+    let generated_code = generate_internal(prepared_modules, settings);
+
+    if let Some(destination) = destination {
+        write_generated_code(destination.as_ref(), &generated_code)?;
+    };
+
+    Ok(generated_code)
+}
+
+/// Reads and parses query modules, including their fragments.
+fn read_modules(queries_path: &Path) -> Result<Vec<Module>, Error> {
+    let modules = read_query_modules(queries_path)?
+        .into_iter()
+        .map(parse_query_module)
+        .collect::<Result<_, parser::error::Error>>()?;
+    Ok(include_fragments(modules)?)
+}
+
 fn write_generated_code(destination: &Path, generated_code: &str) -> Result<(), Error> {
     Ok(
         std::fs::write(destination, generated_code).map_err(|err| WriteOutputError {
@@ -1,7 +1,7 @@
-use std::path::Path;
+use std::{io::Write, ops::Range, path::Path};
 
-use miette::NamedSource;
-use postgres::Client;
+use miette::{NamedSource, SourceSpan};
+use postgres::{error::SqlState, Client};
 
 use crate::utils::db_err;
 
@@ -10,32 +10,388 @@ use self::error::Error;
 /// Loads PostgreSQL schemas into a database.
 ///
 /// Takes a list of file paths as parameter and loads them in their given order.
+/// Directories are loaded as migrations folders, see [`load_migrations`].
+/// Session settings changed by a schema are reset once it is loaded.
 pub fn load_schema<P: AsRef<Path>>(client: &mut Client, paths: &[P]) -> Result<(), Error> {
     for path in paths {
         let path = path.as_ref();
-        let sql = std::fs::read_to_string(path).map_err(|err| Error::Io {
+        if path.is_dir() {
+            load_migrations(client, path)?;
+            continue;
+        }
+        load_script(client, &path.to_string_lossy(), read(path)?)?;
+    }
+    Ok(())
+}
+
+/// Loads a PostgreSQL schema script named `name` into a database.
+pub(crate) fn load_script(client: &mut Client, name: &str, sql: String) -> Result<(), Error> {
+    execute(client, &sql, 0).map_err(|(msg, help, err_span)| Error::Postgres {
+        msg,
+        help,
+        src: NamedSource::new(name, sql),
+        err_span,
+    })
+}
+
+/// Loads the migrations of a folder into a database.
+///
+/// Migrations are ordered by the version prefixing their file name, following sqlx
+/// (`20240101_add_users.sql`, `20240101_add_users.up.sql`), refinery (`V1__init.sql`)
+/// and dbmate (`20240101_add_users.sql`) naming conventions. Only their `up` part is
+/// loaded: `.down.sql` files are skipped, as well as dbmate's `-- migrate:down` sections.
+/// Two migrations can't share a version.
+pub fn load_migrations<P: AsRef<Path>>(client: &mut Client, dir: P) -> Result<(), Error> {
+    let dir = dir.as_ref();
+    let io_err = |err| Error::Io {
+        path: dir.to_string_lossy().to_string(),
+        err,
+    };
+    let mut migrations = Vec::new();
+    for entry in std::fs::read_dir(dir).map_err(io_err)? {
+        let path = entry.map_err(io_err)?.path();
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let Some(stem) = name.strip_suffix(".sql") else {
+            continue;
+        };
+        if stem.ends_with(".down") {
+            continue;
+        }
+        let version = migration_version(stem).ok_or_else(|| Error::MigrationName {
             path: path.to_string_lossy().to_string(),
-            err,
         })?;
-        client.batch_execute(&sql).map_err(|err| {
-            let msg = format!("{err:#}");
-            let src = NamedSource::new(path.to_string_lossy(), sql);
-            if let Some((position, msg, help)) = db_err(&err) {
-                Error::Postgres {
-                    msg,
-                    help,
-                    src,
-                    err_span: Some((position as usize..position as usize).into()),
+        migrations.push((version, path));
+    }
+    migrations.sort();
+    if let Some([(version, first), (_, second)]) =
+        migrations.windows(2).find(|pair| pair[0].0 == pair[1].0)
+    {
+        return Err(Error::MigrationVersion {
+            version: *version,
+            first: first.to_string_lossy().to_string(),
+            second: second.to_string_lossy().to_string(),
+        });
+    }
+
+    for (_, path) in migrations {
+        let sql = read(&path)?;
+        let up = up_range(&sql);
+        execute(client, &sql[up.clone()], up.start).map_err(|(msg, help, err_span)| {
+            Error::Migration {
+                msg,
+                help,
+                src: NamedSource::new(path.to_string_lossy(), sql.clone()),
+                err_span,
+            }
+        })?;
+    }
+    Ok(())
+}
+
+fn read(path: &Path) -> Result<String, Error> {
+    std::fs::read_to_string(path).map_err(|err| Error::Io {
+        path: path.to_string_lossy().to_string(),
+        err,
+    })
+}
+
+/// Error message, help and span in the source file of a failed script
+type ScriptError = (String, Option<String>, Option<SourceSpan>);
+
+/// psql meta-commands that don't affect the loaded schema
+const IGNORED_META_COMMANDS: [&str; 7] = [
+    "echo",
+    "encoding",
+    "pset",
+    "restrict",
+    "set",
+    "unrestrict",
+    "unset",
+];
+
+/// Executes a SQL script located at `offset` in its source file.
+///
+/// Supports the subset of psql scripts found in `pg_dump` outputs: meta-commands
+/// are ignored, and the data blocks following `COPY ... FROM stdin;` are copied.
+/// Both are only recognized at the start of a line outside of quoted text and comments.
+/// Session settings changed by the script, like the `search_path` emptied by `pg_dump`,
+/// are reset once it is loaded.
+fn execute(client: &mut Client, sql: &str, offset: usize) -> Result<(), ScriptError> {
+    let span = |start: usize, len: usize| Some((offset + start..offset + start + len).into());
+    let mut start = 0;
+    let mut context = Context::Code;
+    let mut sql_lines = lines(sql, 0);
+    while let Some((pos, line)) = sql_lines.next() {
+        let trimmed = line.trim_end();
+        if context != Context::Code {
+            context = context.scan(line);
+        } else if let Some(command) = trimmed.strip_prefix('\\') {
+            batch_execute(client, &sql[start..pos], offset + start)?;
+            let name = command.split_whitespace().next().unwrap_or_default();
+            if matches!(name, "c" | "connect") {
+                return Err((
+                    format!("psql meta-command `\\{name}` is not supported"),
+                    Some(String::from(
+                        "schemas are loaded into a single database, dump or load each database separately",
+                    )),
+                    span(pos, trimmed.len()),
+                ));
+            }
+            if !IGNORED_META_COMMANDS.contains(&name) {
+                return Err((
+                    format!("unsupported psql meta-command `\\{name}`"),
+                    Some(String::from("remove this meta-command from the schema")),
+                    span(pos, trimmed.len()),
+                ));
+            }
+            start = pos + line.len();
+        } else if is_copy_from_stdin(trimmed) {
+            batch_execute(client, &sql[start..pos], offset + start)?;
+            let data_start = pos + line.len();
+            let (data_end, next) = sql_lines
+                .by_ref()
+                .find(|(_, line)| line.trim_end() == "\\.")
+                .map_or((sql.len(), sql.len()), |(end, line)| {
+                    (end, end + line.len())
+                });
+            copy_in(client, trimmed, &sql[data_start..data_end]).map_err(|(msg, help, line)| {
+                // Locate the failing data line, or the statement
+                let line = line
+                    .and_then(|nb: usize| {
+                        lines(&sql[data_start..data_end], data_start).nth(nb.checked_sub(1)?)
+                    })
+                    .map_or_else(
+                        || span(pos, trimmed.len()),
+                        |(pos, line)| span(pos, line.trim_end().len()),
+                    );
+                (msg, help, line)
+            })?;
+            start = next;
+        } else {
+            context = context.scan(line);
+        }
+    }
+    batch_execute(client, &sql[start..], offset + start)?;
+    batch_execute(client, "RESET ALL", 0)?;
+    Ok(())
+}
+
+/// Lexical context of a SQL script at the start of a line
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Context {
+    Code,
+    /// Inside a string or quoted identifier, with backslash escapes for `E'...'` strings
+    Quoted {
+        quote: u8,
+        escapes: bool,
+    },
+    /// Inside a dollar-quoted string, closed by its tag (e.g. `$body$`)
+    DollarQuoted(String),
+    /// Inside nested block comments
+    Comment(usize),
+}
+
+impl Context {
+    /// Context at the end of a line starting in this context.
+    fn scan(mut self, line: &str) -> Self {
+        let bytes = line.as_bytes();
+        let is_ident =
+            |i: usize| i > 0 && (bytes[i - 1].is_ascii_alphanumeric() || bytes[i - 1] == b'_');
+        let mut i = 0;
+        while i < bytes.len() {
+            let rest = &line[i..];
+            match &mut self {
+                Context::Code => match bytes[i] {
+                    quote @ (b'\'' | b'"') => {
+                        let escapes = quote == b'\''
+                            && i > 0
+                            && bytes[i - 1].eq_ignore_ascii_case(&b'e')
+                            && !is_ident(i - 1);
+                        self = Context::Quoted { quote, escapes };
+                    }
+                    b'-' if rest.starts_with("--") => break,
+                    b'/' if rest.starts_with("/*") => {
+                        self = Context::Comment(1);
+                        i += 1;
+                    }
+                    b'$' if !is_ident(i) => {
+                        if let Some(tag) = dollar_tag(rest) {
+                            i += tag.len() - 1;
+                            self = Context::DollarQuoted(tag.to_string());
+                        }
+                    }
+                    _ => {}
+                },
+                Context::Quoted { quote, escapes } => {
+                    if *escapes && bytes[i] == b'\\' {
+                        i += 1;
+                    } else if bytes[i] == *quote {
+                        // Doubled quotes close then reopen the quoted text
+                        self = Context::Code;
+                    }
+                }
+                Context::DollarQuoted(tag) => {
+                    if rest.starts_with(tag.as_str()) {
+                        i += tag.len() - 1;
+                        self = Context::Code;
+                    }
                 }
-            } else {
-                Error::Postgres {
-                    msg,
-                    help: None,
-                    src,
-                    err_span: None,
+                Context::Comment(depth) => {
+                    if rest.starts_with("*/") {
+                        *depth -= 1;
+                        if *depth == 0 {
+                            self = Context::Code;
+                        }
+                        i += 1;
+                    } else if rest.starts_with("/*") {
+                        *depth += 1;
+                        i += 1;
+                    }
                 }
             }
-        })?;
+            i += 1;
+        }
+        self
+    }
+}
+
+/// Tag opening a dollar-quoted string at the start of `text` (e.g. `$$` or `$body$`)
+fn dollar_tag(text: &str) -> Option<&str> {
+    let end = text[1..].find('$')? + 2;
+    let name = &text[1..end - 1];
+    let valid = name
+        .chars()
+        .enumerate()
+        .all(|(i, c)| c == '_' || c.is_alphabetic() || (i > 0 && c.is_ascii_digit()));
+    valid.then_some(&text[..end])
+}
+
+/// Lines of a text located at `offset`, including their terminator, with their position
+fn lines(text: &str, offset: usize) -> impl Iterator<Item = (usize, &str)> {
+    text.split_inclusive('\n').scan(offset, |pos, line| {
+        let line_pos = *pos;
+        *pos += line.len();
+        Some((line_pos, line))
+    })
+}
+
+/// Executes SQL statements located at `offset` in their source file.
+fn batch_execute(client: &mut Client, sql: &str, offset: usize) -> Result<(), ScriptError> {
+    if sql.trim().is_empty() {
+        return Ok(());
+    }
+    client.batch_execute(sql).map_err(|err| {
+        if let Some((position, msg, help)) = db_err(&err) {
+            let position = offset + position as usize;
+            (msg, help, Some((position..position).into()))
+        } else {
+            (format!("{err:#}"), None, None)
+        }
+    })
+}
+
+/// Checks if a line is a `COPY ... FROM stdin;` statement, as written by `pg_dump`
+fn is_copy_from_stdin(line: &str) -> bool {
+    let line = line.to_ascii_uppercase();
+    line.starts_with("COPY ") && line.ends_with(" FROM STDIN;")
+}
+
+/// Copies a data block using a `COPY ... FROM stdin` statement,
+/// returning the error message, help and failing data line number
+fn copy_in(
+    client: &mut Client,
+    stmt: &str,
+    data: &str,
+) -> Result<(), (String, Option<String>, Option<usize>)> {
+    let db_err = |err: postgres::Error| {
+        if let Some(db_err) = err.as_db_error() {
+            // The context of copy errors is formatted as `COPY table, line N...`
+            let line = db_err.where_().and_then(|context| {
+                let (_, line) = context.split_once(", line ")?;
+                let end = line
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(line.len());
+                line[..end].parse().ok()
+            });
+            (
+                db_err.message().to_string(),
+                db_err.hint().map(String::from),
+                line,
+            )
+        } else {
+            (format!("{err:#}"), None, None)
+        }
+    };
+    let mut writer = client.copy_in(stmt).map_err(db_err)?;
+    writer
+        .write_all(data.as_bytes())
+        .map_err(|err| (format!("{err:#}"), None, None))?;
+    writer.finish().map_err(db_err)?;
+    Ok(())
+}
+
+/// Version of a migration from its file name without extension,
+/// the number following an optional refinery `V` or `U` prefix
+fn migration_version(stem: &str) -> Option<u64> {
+    let name = stem
+        .strip_prefix(['V', 'U'])
+        .filter(|name| name.starts_with(|c: char| c.is_ascii_digit()))
+        .unwrap_or(stem);
+    let end = name
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(name.len());
+    name[..end].parse().ok()
+}
+
+/// Range of the `up` part of a migration, delimited by dbmate's `-- migrate:up`
+/// and `-- migrate:down` comments when present, which may be followed by options
+/// (e.g. `-- migrate:up transaction:false`)
+fn up_range(sql: &str) -> Range<usize> {
+    let is_marker = |line: &str, marker: &str| {
+        line.strip_prefix(marker).map_or(false, |rest| {
+            rest.is_empty() || rest.starts_with(char::is_whitespace)
+        })
+    };
+    let mut range = 0..sql.len();
+    let mut offset = 0;
+    for line in sql.split_inclusive('\n') {
+        let line_trimmed = line.trim();
+        if is_marker(line_trimmed, "-- migrate:up") {
+            range.start = offset + line.len();
+        } else if is_marker(line_trimmed, "-- migrate:down") {
+            range.end = offset;
+            break;
+        }
+        offset += line.len();
+    }
+    range
+}
+
+/// Creates PostgreSQL extensions in a database, if they don't already exist.
+///
+/// Extensions must be available in the database server, they are usually created
+/// before loading schemas depending on them.
+pub fn load_extensions<S: AsRef<str>>(client: &mut Client, extensions: &[S]) -> Result<(), Error> {
+    for extension in extensions {
+        let name = extension.as_ref();
+        let quoted = name.replace('"', "\"\"");
+        client
+            .batch_execute(&format!("CREATE EXTENSION IF NOT EXISTS \"{quoted}\";"))
+            .map_err(|err| {
+                let name = name.to_string();
+                // Postgres 15 reports unavailable extensions as unsupported, older versions as missing files
+                match err.code() {
+                    Some(&SqlState::FEATURE_NOT_SUPPORTED | &SqlState::UNDEFINED_FILE) => {
+                        Error::ExtensionUnavailable { name }
+                    }
+                    _ => Error::Extension {
+                        name,
+                        msg: format!("{err:#}"),
+                    },
+                }
+            })?;
     }
     Ok(())
 }
@@ -48,6 +404,35 @@ pub(crate) mod error {
     pub enum Error {
         #[error("Could not read schema `{path}`: ({err})")]
         Io { path: String, err: std::io::Error },
+        #[error("Could not apply migration `{}`: {msg}", .src.name())]
+        Migration {
+            msg: String,
+            #[source_code]
+            src: NamedSource,
+            #[help]
+            help: Option<String>,
+            #[label("error occurs near this location")]
+            err_span: Option<SourceSpan>,
+        },
+        #[error("Migration `{path}` is not prefixed by a version")]
+        #[diagnostic(help(
+            "name migrations like `V1__init.sql`, `20240101_init.sql` or `20240101_init.up.sql`"
+        ))]
+        MigrationName { path: String },
+        #[error("Migrations `{first}` and `{second}` share version {version}")]
+        #[diagnostic(help("give each migration its own version"))]
+        MigrationVersion {
+            version: u64,
+            first: String,
+            second: String,
+        },
+        #[error("Extension `{name}` is not available in the database")]
+        #[diagnostic(help(
+            "use a container image providing this extension, for example `docker.io/postgis/postgis` for `postgis`"
+        ))]
+        ExtensionUnavailable { name: String },
+        #[error("Could not create extension `{name}`: {msg}")]
+        Extension { name: String, msg: String },
         #[error("Could not execute schema: {msg}")]
         Postgres {
             msg: String,
@@ -1,11 +1,11 @@
-use std::{fmt::Display, ops::Range};
+use std::{collections::HashMap, fmt::Display, ops::Range};
 
 use chumsky::prelude::*;
-use error::Error;
+use error::{Error, FragmentError};
 use heck::ToUpperCamelCase;
 use miette::SourceSpan;
 
-use crate::read_queries::ModuleInfo;
+use crate::{read_queries::ModuleInfo, utils::find_duplicate};
 
 /// Th    if is data structure holds a value and the context in which it was parsed.
 /// This context is used for error reporting.
@@ -83,6 +83,20 @@ fn ident() -> impl Parser<char, Span<String>, Error = Simple<char>> {
     plain_ident().or(quoted_ident())
 }
 
+/// Parse a rust path to an existing item, like `crate::model::User`
+fn rust_path() -> impl Parser<char, Span<String>, Error = Simple<char>> {
+    plain_ident()
+        .then(just("::").ignore_then(plain_ident()).repeated().at_least(1))
+        .map_with_span(|(first, rest), span: Range<usize>| Span {
+            value: std::iter::once(first)
+                .chain(rest)
+                .map(|it| it.value)
+                .collect::<Vec<_>>()
+                .join("::"),
+            span: span.into(),
+        })
+}
+
 fn ln() -> impl Parser<char, (), Error = Simple<char>> {
     just("\n").or(just("\n\r")).ignored()
 }
@@ -94,9 +108,9 @@ fn space() -> impl Parser<char, (), Error = Simple<char>> {
 }
 
 fn blank() -> impl Parser<char, (), Error = Simple<char>> {
-    // We want to escape valid SQL comment beginning with -- while not escaping our syntax --: or --!
+    // We want to escape valid SQL comment beginning with -- while not escaping our syntax --:, --! or --@
     let comment = just("--")
-        .then(none_of(":!").rewind())
+        .then(none_of(":!@").rewind())
         .then(none_of('\n').repeated());
     filter(|c: &char| c.is_whitespace())
         .ignored()
@@ -110,6 +124,21 @@ pub struct NullableIdent {
     pub name: Span<String>,
     pub nullable: bool,
     pub inner_nullable: bool,
+    /// Positional parameter named by this ident (`id = $1`)
+    pub position: Option<Span<usize>>,
+}
+
+/// Parse a positional parameter (`$1`)
+fn position() -> impl Parser<char, Span<usize>, Error = Simple<char>> {
+    just('$')
+        .ignore_then(text::int(10).try_map(|nb: String, span| {
+            nb.parse::<usize>()
+                .map_err(|e| Simple::custom(span, e.to_string()))
+        }))
+        .map_with_span(|value, span: Range<usize>| Span {
+            value,
+            span: span.into(),
+        })
 }
 
 fn parse_nullable_ident() -> impl Parser<char, Vec<NullableIdent>, Error = Simple<char>> {
@@ -117,10 +146,18 @@ fn parse_nullable_ident() -> impl Parser<char, Vec<NullableIdent>, Error = Simpl
         .ignore_then(ident())
         .then(just('?').or_not())
         .then(just("[?]").or_not())
-        .map(|((name, null), inner_null)| NullableIdent {
+        .then(
+            space()
+                .ignore_then(just('='))
+                .ignore_then(space())
+                .ignore_then(position())
+                .or_not(),
+        )
+        .map(|(((name, null), inner_null), position)| NullableIdent {
             name,
             nullable: null.is_some(),
             inner_nullable: inner_null.is_some(),
+            position,
         })
         .then_ignore(space())
         .separated_by(just(','))
@@ -128,20 +165,176 @@ fn parse_nullable_ident() -> impl Parser<char, Vec<NullableIdent>, Error = Simpl
         .delimited_by(just('('), just(')'))
 }
 
+/// Rust attributes and visibility declared in an annotation, applied to the generated items.
+#[derive(Debug, Clone, Default)]
+pub struct Attributes {
+    /// Content of each `#[...]` attribute
+    pub attrs: Vec<Span<String>>,
+    pub vis: Option<Span<String>>,
+}
+
+impl Attributes {
+    fn parser() -> impl Parser<char, Self, Error = Simple<char>> {
+        Self::attribute()
+            .then_ignore(space())
+            .repeated()
+            .then(Self::visibility().then_ignore(space()).or_not())
+            .map(|(attrs, vis)| Self { attrs, vis })
+    }
+
+    /// Parse a `#[...]` attribute, keeping its content verbatim
+    fn attribute() -> impl Parser<char, Span<String>, Error = Simple<char>> {
+        let content = recursive(|content| {
+            let string = just('\\')
+                .chain(any())
+                .or(none_of("\"\\\n").map(|c| vec![c]))
+                .repeated()
+                .flatten()
+                .delimited_by(just('"'), just('"'))
+                .collect::<String>()
+                .map(|s| format!("\"{s}\""));
+            let nested = content
+                .delimited_by(just('['), just(']'))
+                .map(|s: String| format!("[{s}]"));
+            string
+                .or(nested)
+                .or(none_of("[]\"\n").map(String::from))
+                .repeated()
+                .map(|parts: Vec<String>| parts.concat())
+        });
+        just("#[")
+            .ignore_then(content)
+            .then_ignore(just(']'))
+            .map_with_span(|value: String, span: Range<usize>| Span {
+                value: value.trim().to_string(),
+                span: span.into(),
+            })
+    }
+
+    /// Parse a visibility modifier like `pub` or `pub(crate)`
+    fn visibility() -> impl Parser<char, Span<String>, Error = Simple<char>> {
+        just("pub")
+            .ignore_then(
+                none_of(")\n")
+                    .repeated()
+                    .delimited_by(just('('), just(')'))
+                    .collect::<String>()
+                    .or_not(),
+            )
+            .then_ignore(filter(|c: &char| c.is_whitespace()).rewind())
+            .map_with_span(|restriction, span: Range<usize>| Span {
+                value: restriction
+                    .map_or_else(|| String::from("pub"), |it| format!("pub({})", it.trim())),
+                span: span.into(),
+            })
+    }
+
+    /// Arguments of an attribute of the form `name(...)`
+    fn args<'a>(attr: &'a str, name: &str) -> Option<&'a str> {
+        attr.strip_prefix(name)?
+            .trim_start()
+            .strip_prefix('(')?
+            .strip_suffix(')')
+    }
+
+    /// Traits listed in `derive(...)` attributes
+    pub fn derives(&self) -> impl Iterator<Item = &str> {
+        self.attrs
+            .iter()
+            .filter_map(|it| Self::args(&it.value, "derive"))
+            .flat_map(|it| it.split(','))
+            .map(str::trim)
+            .filter(|it| !it.is_empty())
+    }
+
+    /// Attributes that only make sense on a struct (`derive` and `serde`)
+    fn is_struct_attr(attr: &str) -> bool {
+        Self::args(attr, "derive").is_some() || Self::args(attr, "serde").is_some()
+    }
+
+    /// Attributes applying to a struct, excluding derives
+    pub fn struct_attrs(&self) -> impl Iterator<Item = &str> {
+        self.attrs
+            .iter()
+            .map(|it| it.value.as_str())
+            .filter(|it| Self::args(it, "derive").is_none())
+    }
+
+    /// Attributes applying to items generated for a query
+    pub fn item_attrs(&self) -> impl Iterator<Item = &str> {
+        self.attrs
+            .iter()
+            .map(|it| it.value.as_str())
+            .filter(|it| !Self::is_struct_attr(it))
+    }
+
+    /// Is the query marked with `#[deprecated]`
+    pub fn is_deprecated(&self) -> bool {
+        self.attrs.iter().any(|it| {
+            it.value.strip_prefix("deprecated").map_or(false, |rest| {
+                rest.is_empty() || rest.trim_start().starts_with(['=', '('])
+            })
+        })
+    }
+
+    /// Conditional compilation attributes applying to every generated item
+    pub fn cfg_attrs(&self) -> impl Iterator<Item = &str> {
+        self.attrs
+            .iter()
+            .map(|it| it.value.as_str())
+            .filter(|it| Self::args(it, "cfg").is_some())
+    }
+
+    /// Keep only the attributes that a query can pass down to its inline structs
+    pub(crate) fn for_inline_struct(&self) -> Self {
+        Self {
+            attrs: self
+                .attrs
+                .iter()
+                .filter(|it| Self::is_struct_attr(&it.value))
+                .cloned()
+                .collect(),
+            vis: None,
+        }
+    }
+
+    /// Add the attributes of `other` that are not already present
+    pub(crate) fn merge(&mut self, other: &Self) {
+        for attr in &other.attrs {
+            if !self.attrs.contains(attr) {
+                self.attrs.push(attr.clone());
+            }
+        }
+        if self.vis.is_none() {
+            self.vis = other.vis.clone();
+        }
+    }
+
+    pub fn is_crate_visible(&self) -> bool {
+        self.vis.as_ref().map_or(false, |it| it.value != "pub")
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TypeAnnotation {
     pub name: Span<String>,
     pub fields: Vec<NullableIdent>,
+    pub attributes: Attributes,
 }
 
 impl TypeAnnotation {
     fn parser() -> impl Parser<char, Self, Error = Simple<char>> {
         just("--:")
             .ignore_then(space())
-            .ignore_then(ident())
+            .ignore_then(Attributes::parser())
+            .then(ident())
             .then_ignore(space())
             .then(parse_nullable_ident())
-            .map(|(name, fields)| Self { name, fields })
+            .map(|((attributes, name), fields)| Self {
+                name,
+                fields,
+                attributes,
+            })
     }
 }
 
@@ -152,12 +345,24 @@ pub(crate) struct Query {
     pub(crate) row: QueryDataStruct,
     pub(crate) sql_span: SourceSpan,
     pub(crate) sql_str: String,
+    /// Origin of the expanded sql query
+    pub(crate) sql_map: SqlMap,
     pub(crate) bind_params: Vec<Span<String>>,
+    /// Distinct `$N` placeholders, ordered by position
+    pub(crate) positional_params: Vec<Span<usize>>,
+    pub(crate) attributes: Attributes,
+    pub(crate) cardinality: Option<Span<Cardinality>>,
+    pub(crate) expect: Option<Span<Expect>>,
 }
 
 impl Query {
     /// Escape sql string and pattern that are not bind
     fn sql_escaping() -> impl Parser<char, (), Error = Simple<char>> {
+        Self::sql_token().repeated().at_least(1).ignored()
+    }
+
+    /// A single sql token that is not a bind or an include
+    fn sql_token() -> impl Parser<char, (), Error = Simple<char>> {
         // https://www.postgresql.org/docs/current/sql-syntax-lexical.html
 
         // ::bind
@@ -180,82 +385,157 @@ impl Query {
             .repeated()
             .delimited_by(just("e'").or(just("E'")), just("'"))
             .ignored();
-        // $:bind$:bind$:bind$
-        let dollar_tag = just("$").then(none_of("$").repeated()).then(just("$"));
-        let dollar_quoted = none_of("$")
-            .repeated()
-            .delimited_by(dollar_tag.clone(), dollar_tag)
+        // $tag$:bind$tag$
+        let dollar_tag = filter(|c: &char| c.is_ascii_alphabetic() || *c == '_')
+            .chain(filter(|c: &char| c.is_ascii_alphanumeric() || *c == '_').repeated())
+            .or_not()
+            .map(Option::unwrap_or_default)
+            .delimited_by(just('$'), just('$'))
+            .collect::<String>();
+        let dollar_quoted = dollar_tag
+            .then_with(|tag| take_until(just(format!("${tag}$"))))
             .ignored();
+        // -- :bind
+        let line_comment = just("--").then(none_of("\n").repeated()).ignored();
+        // /* :bind */
+        let block_comment = just("/*").then(take_until(just("*/"))).ignored();
 
         c_style_string
+            .or(line_comment)
+            .or(block_comment)
             .or(cast)
             .or(string)
             .or(constant)
             .or(dollar_quoted)
             // Non c_style_string e
             .or(one_of("eE").then(none_of("'").rewind()).ignored())
+            // Non positional $
+            .or(just('$')
+                .then(filter(|c: &char| !c.is_ascii_digit()).rewind())
+                .ignored())
+            // Non binding @ operators
+            .or(just('@')
+                .then(
+                    filter(|c: &char| !c.is_ascii_alphanumeric() && *c != '_' && *c != '"')
+                        .rewind(),
+                )
+                .ignored())
             // Non binding sql
-            .or(none_of("\"':$eE").ignored())
-            .repeated()
-            .at_least(1)
-            .ignored()
+            .or(none_of("\"':$@eE").ignored())
     }
 
     /// Parse all bind from an SQL query
-    fn parse_bind() -> impl Parser<char, Vec<Span<String>>, Error = Simple<char>> {
-        just(':')
-            .ignore_then(plain_ident())
+    fn parse_bind() -> impl Parser<char, Vec<Bind>, Error = Simple<char>> {
+        one_of(":@")
+            .ignore_then(ident())
+            .map(Bind::Named)
+            .or(position().map(Bind::Positional))
             .separated_by(Self::sql_escaping())
             .allow_leading()
             .allow_trailing()
     }
 
-    /// Parse sql query, normalizing named parameters
-    fn parse_sql_query(
-    ) -> impl Parser<char, (String, SourceSpan, Vec<Span<String>>), Error = Simple<char>> {
+    /// Parse raw sql query, binds are normalized once fragments are included
+    fn parse_sql_query() -> impl Parser<char, (String, SourceSpan), Error = Simple<char>> {
         none_of(";")
             .repeated()
             .then_ignore(just(';'))
             .collect::<String>()
-            .map_with_span(|mut sql_str, span: Range<usize>| {
-                let bind_params: Vec<_> = Self::parse_bind().parse(sql_str.clone()).unwrap();
-                // Remove duplicate
-                let dedup_params: Vec<_> = bind_params
-                    .iter()
-                    .enumerate()
-                    .rev()
-                    .filter(|(i, u)| !bind_params[..*i].contains(u))
-                    .map(|(_, u)| u.clone())
-                    .rev()
-                    .collect();
-
-                for bind_param in bind_params.iter().rev() {
-                    let index = dedup_params.iter().position(|bp| bp == bind_param).unwrap();
-                    let start = bind_param.span.offset() - 1;
-                    let end = start + bind_param.span.len();
-                    sql_str.replace_range(start..=end, &format!("${}", index + 1));
-                }
+            .map_with_span(|sql_str, span: Range<usize>| (sql_str, span.into()))
+    }
+
+    /// `@` is also an operator character, `@name` is only a parameter where an operand starts
+    fn is_named_bind(&self, bind: &Span<String>) -> bool {
+        let sigil = bind.span.offset() - 1;
+        let sql = self.sql_str.as_bytes();
+        sql[sigil] == b':'
+            || sigil == 0
+            || matches!(sql[sigil - 1], b'(' | b',')
+            || sql[sigil - 1].is_ascii_whitespace()
+    }
 
-                (sql_str, span.into(), dedup_params)
+    /// Normalize named parameters of the expanded sql query
+    fn normalize_binds(&mut self) {
+        let binds = Self::parse_bind().parse(self.sql_str.clone()).unwrap();
+        let mut bind_params = Vec::new();
+        let mut positional_params: Vec<Span<usize>> = Vec::new();
+        for bind in binds {
+            match bind {
+                Bind::Named(it) if self.is_named_bind(&it) => bind_params.push(it),
+                Bind::Named(_) => {}
+                Bind::Positional(it) => positional_params.push(it),
+            }
+        }
+        // Remove duplicate
+        let dedup_params: Vec<_> = bind_params
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(i, u)| !bind_params[..*i].contains(u))
+            .map(|(_, u)| u.clone())
+            .rev()
+            .collect();
+
+        for bind_param in bind_params.iter().rev() {
+            let index = dedup_params.iter().position(|bp| bp == bind_param).unwrap();
+            let start = bind_param.span.offset() - 1;
+            let end = start + bind_param.span.len();
+            self.sql_str
+                .replace_range(start..=end, &format!("${}", index + 1));
+        }
+        positional_params.sort();
+        positional_params.dedup();
+
+        // Bind spans are relative to the expanded query
+        self.bind_params = dedup_params
+            .into_iter()
+            .map(|it| Span {
+                span: self.sql_map.local(it.span),
+                value: it.value,
+            })
+            .collect();
+        self.positional_params = positional_params
+            .into_iter()
+            .map(|it| Span {
+                span: self.sql_map.local(it.span),
+                value: it.value,
             })
+            .collect();
     }
 
-    fn parse_query_annotation(
-    ) -> impl Parser<char, (Span<String>, QueryDataStruct, QueryDataStruct), Error = Simple<char>>
-    {
+    fn parse_query_annotation() -> impl Parser<char, QueryAnnotation, Error = Simple<char>> {
+        let row = just(':')
+            .ignore_then(space())
+            .ignore_then(QueryDataStruct::parser())
+            .then_ignore(space())
+            .then(Cardinality::parser().or_not());
         just("--!")
             .ignore_then(space())
-            .ignore_then(plain_ident())
+            .ignore_then(Attributes::parser())
+            .then(plain_ident())
             .then_ignore(space())
             .then(QueryDataStruct::parser())
             .then_ignore(space())
             .then(
-                just(':')
-                    .ignore_then(space())
-                    .ignore_then(QueryDataStruct::parser())
+                // A cardinality marker looks like a row, try it first
+                Cardinality::parser()
+                    .map(|cardinality| (None, Some(cardinality)))
+                    .or(row.map(|(row, cardinality)| (Some(row), cardinality)))
                     .or_not(),
             )
-            .map(|((name, param), row)| (name, param, row.unwrap_or_default()))
+            .then_ignore(space())
+            .then(Expect::parser().or_not())
+            .map(|((((attributes, name), param), row), expect)| {
+                let (row, cardinality) = row.unwrap_or_default();
+                QueryAnnotation {
+                    attributes,
+                    name,
+                    param,
+                    row: row.unwrap_or_default(),
+                    cardinality,
+                    expect,
+                }
+            })
     }
 
     fn parser() -> impl Parser<char, Self, Error = Simple<char>> {
@@ -264,18 +544,126 @@ impl Query {
             .then_ignore(ln())
             .then(Self::parse_sql_query())
             .map(
-                |((name, param, row), (sql_str, sql_span, bind_params))| Self {
+                |(
+                    QueryAnnotation {
+                        attributes,
+                        name,
+                        param,
+                        row,
+                        cardinality,
+                        expect,
+                    },
+                    (sql_str, sql_span),
+                )| Self {
                     name,
                     param,
                     row,
+                    sql_map: SqlMap::new(sql_span.offset()),
                     sql_span,
                     sql_str,
-                    bind_params,
+                    bind_params: Vec::new(),
+                    positional_params: Vec::new(),
+                    attributes,
+                    cardinality,
+                    expect,
                 },
             )
     }
 }
 
+/// A bind parameter in a SQL query
+enum Bind {
+    /// `:name`, `:"name"` or `@name`
+    Named(Span<String>),
+    /// `$1`
+    Positional(Span<usize>),
+}
+
+/// Content of a `--!` annotation
+struct QueryAnnotation {
+    attributes: Attributes,
+    name: Span<String>,
+    param: QueryDataStruct,
+    row: QueryDataStruct,
+    cardinality: Option<Span<Cardinality>>,
+    expect: Option<Span<Expect>>,
+}
+
+/// Number of rows a query is expected to return, generating a direct function
+/// instead of the default query builder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Cardinality {
+    /// Exactly one row
+    One,
+    /// Zero or one row
+    Opt,
+    /// Any number of rows
+    Many,
+    /// Only the number of affected rows
+    ExecRows,
+}
+
+impl Cardinality {
+    fn parser() -> impl Parser<char, Span<Self>, Error = Simple<char>> {
+        just(':')
+            .ignore_then(choice((
+                just("one").to(Self::One),
+                just("opt").to(Self::Opt),
+                just("many").to(Self::Many),
+                just("exec_rows").to(Self::ExecRows),
+            )))
+            .then_ignore(filter(|c: &char| c.is_whitespace()).rewind())
+            .map_with_span(|value, span: Range<usize>| Span {
+                value,
+                span: span.into(),
+            })
+    }
+}
+
+impl Display for Cardinality {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::One => ":one",
+            Self::Opt => ":opt",
+            Self::Many => ":many",
+            Self::ExecRows => ":exec_rows",
+        })
+    }
+}
+
+/// Exact number of rows an execute query must affect, declared with `!expect N`
+/// and optionally followed by `rollback` to undo the statement on mismatch. Rolling back uses a
+/// savepoint, so these queries must run in a transaction or fail with `RowCountError::NoTransaction`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Expect {
+    pub(crate) rows: u64,
+    pub(crate) rollback: bool,
+}
+
+impl Expect {
+    fn parser() -> impl Parser<char, Span<Self>, Error = Simple<char>> {
+        just("!expect")
+            .ignore_then(space())
+            .ignore_then(text::int(10).try_map(|nb: String, span| {
+                nb.parse::<u64>()
+                    .map_err(|e| Simple::custom(span, e.to_string()))
+            }))
+            .then(
+                space()
+                    .ignore_then(just("rollback"))
+                    .then_ignore(filter(|c: &char| c.is_whitespace()).rewind())
+                    .or_not(),
+            )
+            .map_with_span(|(rows, rollback), span: Range<usize>| Span {
+                value: Self {
+                    rows,
+                    rollback: rollback.is_some(),
+                },
+                span: span.into(),
+            })
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct QueryDataStruct {
     pub span: SourceSpan,
@@ -296,6 +684,21 @@ impl QueryDataStruct {
         self.idents.is_some() && self.name.is_some()
     }
 
+    /// Is this a path to a user-defined struct instead of a generated one
+    pub fn is_external(&self) -> bool {
+        self.name
+            .as_ref()
+            .map_or(false, |it| it.value.contains("::"))
+    }
+
+    /// Is this a reference to a type declared in the shared types module
+    pub(crate) fn is_shared(&self, local: &[TypeAnnotation], types: &[TypeAnnotation]) -> bool {
+        self.idents.is_none()
+            && self.name.as_ref().map_or(false, |name| {
+                local.iter().all(|it| it.name != *name) && types.iter().any(|it| it.name == *name)
+            })
+    }
+
     pub(crate) fn name_and_fields<'a>(
         &'a self,
         registered_structs: &'a [TypeAnnotation],
@@ -330,6 +733,25 @@ impl QueryDataStruct {
     }
 }
 
+impl QueryDataStruct {
+    /// Attributes of the generated struct, either declared with the named type or
+    /// inherited from the query annotation for inline and implicit structs.
+    pub(crate) fn attributes(
+        &self,
+        registered_structs: &[TypeAnnotation],
+        query_attributes: &Attributes,
+    ) -> Attributes {
+        match (&self.name, &self.idents) {
+            (Some(named), None) => registered_structs
+                .iter()
+                .find(|it| it.name == *named)
+                .map(|it| it.attributes.clone())
+                .unwrap_or_default(),
+            _ => query_attributes.for_inline_struct(),
+        }
+    }
+}
+
 impl Default for QueryDataStruct {
     fn default() -> Self {
         Self {
@@ -342,7 +764,8 @@ impl Default for QueryDataStruct {
 
 impl QueryDataStruct {
     fn parser() -> impl Parser<char, Self, Error = Simple<char>> {
-        plain_ident()
+        rust_path()
+            .or(plain_ident())
             .or_not()
             .then_ignore(space())
             .then(parse_nullable_ident().or_not())
@@ -354,10 +777,96 @@ impl QueryDataStruct {
     }
 }
 
+/// Reusable SQL declared with `--@ fragment name` and included in queries with `{{name}}`
+#[derive(Debug)]
+pub(crate) struct Fragment {
+    pub(crate) name: Span<String>,
+    pub(crate) sql: Span<String>,
+}
+
+impl Fragment {
+    fn parser() -> impl Parser<char, Self, Error = Simple<char>> {
+        just("--@")
+            .ignore_then(space())
+            .ignore_then(just("fragment"))
+            .ignore_then(space())
+            .ignore_then(plain_ident())
+            .then_ignore(space())
+            .then_ignore(ln())
+            .then(
+                none_of(";")
+                    .repeated()
+                    .collect::<String>()
+                    .map_with_span(|value, span: Range<usize>| Span {
+                        value,
+                        span: span.into(),
+                    })
+                    .then_ignore(just(';')),
+            )
+            .map(|(name, sql)| Self { name, sql })
+    }
+}
+
+/// Maps offsets of an expanded sql query back to the modules it was built from
+#[derive(Debug, Clone)]
+pub(crate) struct SqlMap {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone)]
+struct Segment {
+    /// Start in the expanded sql query
+    start: usize,
+    /// Start in the module declaring this part
+    offset: usize,
+    /// Fragment module and include site in the query module, `None` for the query module itself
+    fragment: Option<(ModuleInfo, SourceSpan)>,
+}
+
+impl SqlMap {
+    fn new(offset: usize) -> Self {
+        Self {
+            segments: vec![Segment {
+                start: 0,
+                offset,
+                fragment: None,
+            }],
+        }
+    }
+
+    fn segment(&self, pos: usize) -> &Segment {
+        self.segments
+            .iter()
+            .rev()
+            .find(|it| it.start <= pos)
+            .unwrap_or(&self.segments[0])
+    }
+
+    /// Span in the query module, included fragments are located at their include site
+    pub(crate) fn local(&self, span: SourceSpan) -> SourceSpan {
+        let segment = self.segment(span.offset());
+        match &segment.fragment {
+            Some((_, site)) => *site,
+            None => (segment.offset + span.offset() - segment.start, span.len()).into(),
+        }
+    }
+
+    /// Module declaring this offset, `None` for the query module itself, and offset in it
+    pub(crate) fn source(&self, pos: usize) -> (Option<&ModuleInfo>, usize) {
+        let segment = self.segment(pos);
+        (
+            segment.fragment.as_ref().map(|(info, _)| info),
+            segment.offset + pos - segment.start,
+        )
+    }
+}
+
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
 enum Statement {
     Type(TypeAnnotation),
     Query(Query),
+    Fragment(Fragment),
 }
 
 #[derive(Debug)]
@@ -365,12 +874,14 @@ pub(crate) struct Module {
     pub(crate) info: ModuleInfo,
     pub(crate) types: Vec<TypeAnnotation>,
     pub(crate) queries: Vec<Query>,
+    pub(crate) fragments: Vec<Fragment>,
 }
 
 pub(crate) fn parse_query_module(info: ModuleInfo) -> Result<Module, Error> {
     match TypeAnnotation::parser()
         .map(Statement::Type)
         .or(Query::parser().map(Statement::Query))
+        .or(Fragment::parser().map(Statement::Fragment))
         .separated_by(blank())
         .allow_leading()
         .allow_trailing()
@@ -380,16 +891,19 @@ pub(crate) fn parse_query_module(info: ModuleInfo) -> Result<Module, Error> {
         Ok(statements) => {
             let mut types = Vec::new();
             let mut queries = Vec::new();
+            let mut fragments = Vec::new();
             for item in statements {
                 match item {
                     Statement::Type(it) => types.push(it),
                     Statement::Query(it) => queries.push(it),
+                    Statement::Fragment(it) => fragments.push(it),
                 }
             }
             Ok(Module {
                 info,
                 types,
                 queries,
+                fragments,
             })
         }
         Err(e) => Err(Error {
@@ -400,6 +914,155 @@ pub(crate) fn parse_query_module(info: ModuleInfo) -> Result<Module, Error> {
     }
 }
 
+/// Include fragments declared in any module into the queries, then normalize their binds
+pub(crate) fn include_fragments(
+    mut modules: Vec<Module>,
+) -> Result<Vec<Module>, Box<FragmentError>> {
+    duplicate_fragment(&modules)?;
+    let fragments: HashMap<_, _> = modules
+        .iter()
+        .flat_map(|module| {
+            module
+                .fragments
+                .iter()
+                .map(move |it| (it.name.value.clone(), (&module.info, &it.sql)))
+        })
+        .collect();
+    let mut expanded = Vec::new();
+    for module in &modules {
+        for query in &module.queries {
+            if query.sql_str.contains("{{") {
+                let mut sql = String::new();
+                let mut segments = Vec::new();
+                expand(
+                    &mut Expansion {
+                        fragments: &fragments,
+                        query_info: &module.info,
+                        sql: &mut sql,
+                        segments: &mut segments,
+                        stack: Vec::new(),
+                    },
+                    &query.sql_str,
+                    query.sql_span.offset(),
+                    None,
+                )?;
+                expanded.push(Some((sql, SqlMap { segments })));
+            } else {
+                expanded.push(None);
+            }
+        }
+    }
+    let mut expanded = expanded.into_iter();
+    for query in modules.iter_mut().flat_map(|it| it.queries.iter_mut()) {
+        if let Some((sql, sql_map)) = expanded.next().flatten() {
+            query.sql_str = sql;
+            query.sql_map = sql_map;
+        }
+        query.normalize_binds();
+    }
+    Ok(modules)
+}
+
+/// Fragments are shared by all modules, their names must be unique across modules
+fn duplicate_fragment(modules: &[Module]) -> Result<(), Box<FragmentError>> {
+    let fragments: Vec<_> = modules
+        .iter()
+        .flat_map(|module| module.fragments.iter().map(move |it| (&module.info, it)))
+        .collect();
+    find_duplicate(&fragments, |a, b| a.1.name == b.1.name).map_or(
+        Ok(()),
+        |((first_info, first), (info, second))| {
+            Err(Box::new(FragmentError::Duplicate {
+                src: (*info).into(),
+                name: second.name.value.clone(),
+                first_path: first_info.path.to_string_lossy().to_string(),
+                first: (first_info.path == info.path).then_some(first.name.span),
+                second: second.name.span,
+            }))
+        },
+    )
+}
+
+struct Expansion<'a> {
+    fragments: &'a HashMap<String, (&'a ModuleInfo, &'a Span<String>)>,
+    query_info: &'a ModuleInfo,
+    sql: &'a mut String,
+    segments: &'a mut Vec<Segment>,
+    /// Fragments being expanded
+    stack: Vec<String>,
+}
+
+/// Expand `{{name}}` includes of `src`, declared at `offset` of its module. `origin`
+/// holds the fragment module and include site when `src` is a fragment.
+fn expand(
+    ctx: &mut Expansion,
+    src: &str,
+    offset: usize,
+    origin: Option<(&ModuleInfo, SourceSpan)>,
+) -> Result<(), Box<FragmentError>> {
+    let push = |ctx: &mut Expansion, start: usize, text: &str| {
+        ctx.segments.push(Segment {
+            start: ctx.sql.len(),
+            offset: offset + start,
+            fragment: origin.map(|(info, site)| (info.clone(), site)),
+        });
+        ctx.sql.push_str(text);
+    };
+    let mut cursor = 0;
+    for (range, name) in find_includes(src) {
+        push(ctx, cursor, &src[cursor..range.start]);
+        let span: SourceSpan = (offset + range.start, range.len()).into();
+        let info = origin.map_or(ctx.query_info, |(info, _)| info);
+        let Some((fragment_info, fragment)) = ctx.fragments.get(&name).copied() else {
+            return Err(Box::new(FragmentError::Unknown {
+                src: info.into(),
+                name,
+                pos: span,
+            }));
+        };
+        if ctx.stack.contains(&name) {
+            return Err(Box::new(FragmentError::Recursive {
+                src: info.into(),
+                name,
+                pos: span,
+            }));
+        }
+        ctx.stack.push(name);
+        let site = origin.map_or(span, |(_, site)| site);
+        expand(
+            ctx,
+            &fragment.value,
+            fragment.span.offset(),
+            Some((fragment_info, site)),
+        )?;
+        ctx.stack.pop();
+        cursor = range.end;
+    }
+    push(ctx, cursor, &src[cursor..]);
+    Ok(())
+}
+
+/// Find the `{{name}}` includes of `src` outside of literals and comments, with their byte range
+fn find_includes(src: &str) -> Vec<(Range<usize>, String)> {
+    let include = filter(|c: &char| c.is_ascii_alphanumeric() || *c == '_')
+        .repeated()
+        .at_least(1)
+        .collect::<String>()
+        .delimited_by(just("{{"), just("}}"))
+        .map_with_span(|name, span: Range<usize>| Some((span, name)));
+    let parser = include
+        .or(Query::sql_token().to(None))
+        .or(any().to(None))
+        .repeated()
+        .map(|it| it.into_iter().flatten().collect());
+    // Spans in bytes, to slice `src`
+    let stream = chumsky::Stream::from_iter(
+        src.len()..src.len(),
+        src.char_indices().map(|(i, c)| (c, i..i + c.len_utf8())),
+    );
+    parser.parse(stream).unwrap_or_default()
+}
+
 pub(crate) mod error {
     use miette::{Diagnostic, NamedSource, SourceSpan};
     use thiserror::Error as ThisError;
@@ -416,4 +1079,40 @@ pub(crate) mod error {
         #[label("unexpected token")]
         pub err_span: SourceSpan,
     }
+
+    #[derive(Debug, ThisError, Diagnostic)]
+    pub enum FragmentError {
+        #[error("the fragment `{name}` is defined multiple time")]
+        #[diagnostic(help(
+            "fragments are shared by all modules, `{name}` is first defined in `{first_path}`"
+        ))]
+        Duplicate {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            first_path: String,
+            #[label("previous definition here")]
+            first: Option<SourceSpan>,
+            #[label("redefined here")]
+            second: SourceSpan,
+        },
+        #[error("unknown fragment `{name}`")]
+        #[diagnostic(help("define it using `--@ fragment {name}` in any query file"))]
+        Unknown {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("no fragment with this name was found")]
+            pos: SourceSpan,
+        },
+        #[error("the fragment `{name}` includes itself")]
+        #[diagnostic(help("remove the recursive include"))]
+        Recursive {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("recursive include")]
+            pos: SourceSpan,
+        },
+    }
 }
@@ -7,8 +7,8 @@ use postgres_types::{Kind, Type};
 
 use crate::{
     codegen::GenCtx,
-    parser::{Module, NullableIdent, Query, Span, TypeAnnotation},
-    read_queries::ModuleInfo,
+    parser::{Attributes, Cardinality, Expect, Module, NullableIdent, Query, Span, TypeAnnotation},
+    read_queries::{ModuleInfo, SHARED_TYPES_MODULE},
     type_registrar::CornucopiaType,
     type_registrar::TypeRegistrar,
     utils::KEYWORD,
@@ -25,6 +25,9 @@ pub(crate) struct PreparedQuery {
     pub(crate) param: Option<(usize, Vec<usize>)>,
     pub(crate) row: Option<(usize, Vec<usize>)>,
     pub(crate) sql: String,
+    pub(crate) attributes: Attributes,
+    pub(crate) cardinality: Option<Cardinality>,
+    pub(crate) expect: Option<Expect>,
 }
 
 /// A normalized ident replacing all non-alphanumeric characters with an underscore (`_`)
@@ -101,21 +104,48 @@ pub(crate) struct PreparedItem {
     pub(crate) is_copy: bool,
     pub(crate) is_named: bool,
     pub(crate) is_ref: bool,
+    pub(crate) attributes: Attributes,
+    /// Declared in the shared types module and generated in `queries::shared`
+    pub(crate) is_shared: bool,
+    /// Path to a user-defined struct built from the columns instead of a generated one
+    pub(crate) is_external: bool,
 }
 
 impl PreparedItem {
-    pub fn new(name: Span<String>, fields: Vec<PreparedField>, is_implicit: bool) -> Self {
+    pub fn new(
+        name: Span<String>,
+        fields: Vec<PreparedField>,
+        is_implicit: bool,
+        attributes: Attributes,
+        is_shared: bool,
+    ) -> Self {
+        let is_external = name.value.contains("::");
         Self {
-            name,
-            is_copy: fields.iter().all(|f| f.ty.is_copy()),
+            // External structs are built directly from owned values, without borrowed variant
+            is_copy: is_external || fields.iter().all(|f| f.ty.is_copy()),
             is_ref: fields.iter().any(|f| f.ty.is_ref()),
             is_named: !is_implicit || fields.len() > 1,
+            name,
             fields,
+            attributes,
+            is_shared,
+            is_external,
         }
     }
 
+    /// Name of the item without its path, used to name the related generated items
+    pub fn type_name(&self) -> &str {
+        self.name.value.rsplit("::").next().unwrap_or_default()
+    }
+
     pub fn path(&self, ctx: &GenCtx) -> String {
-        ctx.path(ctx.depth - 2, &self.name)
+        if self.is_external {
+            self.name.value.clone()
+        } else if self.is_shared {
+            ctx.path(ctx.depth - 1, format_args!("shared::{}", self.name))
+        } else {
+            ctx.path(ctx.depth - 2, &self.name)
+        }
     }
 }
 
@@ -148,6 +178,40 @@ pub(crate) struct PreparedModule {
 pub(crate) struct Preparation {
     pub(crate) modules: Vec<PreparedModule>,
     pub(crate) types: IndexMap<String, Vec<PreparedType>>,
+    /// Rows and params declared in the shared types module
+    pub(crate) shared: Option<PreparedModule>,
+}
+
+impl Preparation {
+    /// Adds a prepared module, registering the shared rows and params it uses.
+    /// Every module must use a shared item with the same fields.
+    fn add(&mut self, module: PreparedModule) -> Result<(), Error> {
+        if let Some(shared) = &mut self.shared {
+            for (items, shared_items) in [
+                (&module.rows, &mut shared.rows),
+                (&module.params, &mut shared.params),
+            ] {
+                for item in items.values().filter(|it| it.is_shared) {
+                    match shared_items.entry(item.name.clone()) {
+                        Entry::Occupied(mut o) => {
+                            validation::shared_struct_field(
+                                &module.info,
+                                &item.name,
+                                &item.fields,
+                                &o.get().fields,
+                            )?;
+                            o.get_mut().attributes.merge(&item.attributes);
+                        }
+                        Entry::Vacant(v) => {
+                            v.insert(item.clone());
+                        }
+                    }
+                }
+            }
+        }
+        self.modules.push(module);
+        Ok(())
+    }
 }
 
 impl PreparedModule {
@@ -157,10 +221,13 @@ impl PreparedModule {
         name: Span<String>,
         fields: Vec<PreparedField>,
         is_implicit: bool,
+        attributes: Attributes,
+        is_shared: bool,
     ) -> Result<(usize, Vec<usize>), Error> {
         assert!(!fields.is_empty());
         match map.entry(name.clone()) {
-            Entry::Occupied(o) => {
+            Entry::Occupied(mut o) => {
+                o.get_mut().attributes.merge(&attributes);
                 let prev = &o.get();
                 // If the row doesn't contain the same fields as a previously
                 // registered row with the same name...
@@ -177,8 +244,14 @@ impl PreparedModule {
                 Ok((o.index(), indexes))
             }
             Entry::Vacant(v) => {
-                v.insert(PreparedItem::new(name.clone(), fields.clone(), is_implicit));
-                Self::add(info, map, name, fields, is_implicit)
+                v.insert(PreparedItem::new(
+                    name.clone(),
+                    fields.clone(),
+                    is_implicit,
+                    attributes.clone(),
+                    is_shared,
+                ));
+                Self::add(info, map, name, fields, is_implicit, attributes, is_shared)
             }
         }
     }
@@ -188,13 +261,23 @@ impl PreparedModule {
         name: Span<String>,
         fields: Vec<PreparedField>,
         is_implicit: bool,
+        attributes: Attributes,
+        is_shared: bool,
     ) -> Result<(usize, Vec<usize>), Error> {
         let fuck = if fields.len() == 1 && is_implicit {
             name.map(|_| fields[0].unwrapped_name())
         } else {
             name
         };
-        Self::add(&self.info, &mut self.rows, fuck, fields, is_implicit)
+        Self::add(
+            &self.info,
+            &mut self.rows,
+            fuck,
+            fields,
+            is_implicit,
+            attributes,
+            is_shared,
+        )
     }
 
     fn add_param(
@@ -202,45 +285,64 @@ impl PreparedModule {
         name: Span<String>,
         fields: Vec<PreparedField>,
         is_implicit: bool,
+        attributes: Attributes,
+        is_shared: bool,
     ) -> Result<(usize, Vec<usize>), Error> {
-        Self::add(&self.info, &mut self.params, name, fields, is_implicit)
+        Self::add(
+            &self.info,
+            &mut self.params,
+            name,
+            fields,
+            is_implicit,
+            attributes,
+            is_shared,
+        )
     }
 
-    fn add_query(
-        &mut self,
-        name: Span<String>,
-        param_idx: Option<(usize, Vec<usize>)>,
-        row_idx: Option<(usize, Vec<usize>)>,
-        sql: String,
-    ) {
-        self.queries.insert(
-            name.clone(),
-            PreparedQuery {
-                ident: Ident::new(name.value),
-                row: row_idx,
-                sql,
-                param: param_idx,
-            },
-        );
+    fn add_query(&mut self, name: Span<String>, query: PreparedQuery) {
+        self.queries.insert(name, query);
     }
 }
 
 /// Prepares all modules
 pub(crate) fn prepare(client: &mut Client, modules: Vec<Module>) -> Result<Preparation, Error> {
     let mut registrar = TypeRegistrar::default();
-    let mut tmp = Preparation {
-        modules: Vec::new(),
-        types: IndexMap::new(),
-    };
     let declared: Vec<_> = modules
         .iter()
         .flat_map(|it| &it.types)
         .map(|ty| (*ty).clone())
         .collect();
+    let (shared, modules): (Vec<_>, Vec<_>) = modules
+        .into_iter()
+        .partition(|it| it.info.name == SHARED_TYPES_MODULE);
+    let shared = shared.into_iter().next();
+    if let Some(shared) = &shared {
+        validation::validate_shared_module(shared, &modules)?;
+    }
+    let mut tmp = Preparation {
+        modules: Vec::new(),
+        types: IndexMap::new(),
+        shared: shared.as_ref().map(|it| PreparedModule {
+            info: it.info.clone(),
+            queries: IndexMap::new(),
+            params: IndexMap::new(),
+            rows: IndexMap::new(),
+        }),
+    };
+    let shared_types = shared
+        .as_ref()
+        .map_or(&[] as &[_], |it| it.types.as_slice());
 
     for module in modules {
-        tmp.modules
-            .push(prepare_module(client, module, &mut registrar)?);
+        tmp.add(prepare_module(
+            client,
+            module,
+            &mut registrar,
+            shared_types,
+        )?)?;
+    }
+    if let Some(shared) = &tmp.shared {
+        validation::validate_preparation(shared)?;
     }
 
     // Prepare types grouped by schema
@@ -259,10 +361,6 @@ pub(crate) fn prepare(client: &mut Client, modules: Vec<Module>) -> Result<Prepa
     Ok(tmp)
 }
 
-fn normalize_rust_name(name: &str) -> String {
-    name.replace(':', "_")
-}
-
 /// Prepares database custom types
 fn prepare_type(
     registrar: &TypeRegistrar,
@@ -320,8 +418,11 @@ fn prepare_module(
     client: &mut Client,
     module: Module,
     registrar: &mut TypeRegistrar,
+    shared_types: &[TypeAnnotation],
 ) -> Result<PreparedModule, Error> {
-    validation::validate_module(&module)?;
+    validation::validate_module(&module, shared_types)?;
+    // Local declarations shadow shared ones
+    let types: Vec<_> = module.types.iter().chain(shared_types).cloned().collect();
 
     let mut tmp_prepared_module = PreparedModule {
         info: module.info.clone(),
@@ -335,6 +436,7 @@ fn prepare_module(
             client,
             &mut tmp_prepared_module,
             registrar,
+            &types,
             &module.types,
             query,
             &module.info,
@@ -352,23 +454,35 @@ fn prepare_query(
     module: &mut PreparedModule,
     registrar: &mut TypeRegistrar,
     types: &[TypeAnnotation],
+    local_types: &[TypeAnnotation],
     Query {
         name,
         param,
         bind_params,
+        positional_params,
         row,
         sql_str,
         sql_span,
+        sql_map,
+        attributes,
+        cardinality,
+        expect,
     }: Query,
     module_info: &ModuleInfo,
 ) -> Result<(), Error> {
+    let (nullable_params_fields, params_name) = param.name_and_fields(types, &name, Some("Params"));
+    let (nullable_row_fields, row_name) = row.name_and_fields(types, &name, None);
+    let bind_params = validation::positional_params(
+        &module.info,
+        &bind_params,
+        &positional_params,
+        nullable_params_fields,
+    )?;
+
     // Prepare the statement
     let stmt = client
         .prepare(&sql_str)
-        .map_err(|e| Error::new_db_err(&e, module_info, &sql_span, &name))?;
-
-    let (nullable_params_fields, params_name) = param.name_and_fields(types, &name, Some("Params"));
-    let (nullable_row_fields, row_name) = row.name_and_fields(types, &name, None);
+        .map_err(|e| Error::new_db_err(&e, module_info, &sql_map, &name))?;
     let params_fields = {
         let stmt_params = stmt.params();
         let params = bind_params
@@ -405,6 +519,22 @@ fn prepare_query(
         let stmt_cols = stmt.columns();
         // Check for row declaration on execute
         validation::row_on_execute(&module.info, &name, &sql_span, &row, stmt_cols)?;
+        validation::cardinality(
+            &module.info,
+            &name,
+            &sql_span,
+            &cardinality,
+            &row,
+            stmt_cols,
+        )?;
+        validation::expect_on_rows(
+            &module.info,
+            &name,
+            &sql_span,
+            &expect,
+            &cardinality,
+            stmt_cols,
+        )?;
         // Check for duplicate names
         validation::duplicate_sql_col_name(&module.info, &name, stmt_cols).map_err(Error::from)?;
         for nullable_col in nullable_row_fields {
@@ -412,6 +542,7 @@ fn prepare_query(
             validation::nullable_column_name(&module.info, nullable_col, stmt_cols)
                 .map_err(Error::from)?;
         }
+        validation::external_row_fields(&module.info, &row, stmt_cols)?;
 
         let mut row_fields = Vec::new();
         for (col_name, col_ty) in stmt_cols.iter().map(|c| (c.name().to_owned(), c.type_())) {
@@ -422,26 +553,46 @@ fn prepare_query(
             let ty = registrar
                 .register(&col_name, col_ty, &name, module_info)?
                 .clone();
-            row_fields.push(PreparedField::new(
-                normalize_rust_name(&col_name),
-                ty,
-                nullity,
-            ));
+            row_fields.push(PreparedField::new(col_name, ty, nullity));
         }
         row_fields
     };
 
-    let row_idx = if row_fields.is_empty() {
+    let exec_rows = cardinality.as_ref().map(|c| c.value) == Some(Cardinality::ExecRows);
+    let row_idx = if row_fields.is_empty() || exec_rows {
         None
     } else {
-        Some(module.add_row(row_name, row_fields, row.is_implicit())?)
+        Some(module.add_row(
+            row_name,
+            row_fields,
+            row.is_implicit(),
+            row.attributes(types, &attributes),
+            row.is_shared(local_types, types),
+        )?)
     };
     let param_idx = if params_fields.is_empty() {
         None
     } else {
-        Some(module.add_param(params_name, params_fields, param.is_implicit())?)
+        Some(module.add_param(
+            params_name,
+            params_fields,
+            param.is_implicit(),
+            param.attributes(types, &attributes),
+            param.is_shared(local_types, types),
+        )?)
     };
-    module.add_query(name.clone(), param_idx, row_idx, sql_str);
+    module.add_query(
+        name.clone(),
+        PreparedQuery {
+            ident: Ident::new(name.value),
+            param: param_idx,
+            row: row_idx,
+            sql: sql_str,
+            attributes,
+            cardinality: cardinality.map(|c| c.value),
+            expect: expect.map(|e| e.value),
+        },
+    );
 
     Ok(())
 }
@@ -451,8 +602,11 @@ pub(crate) mod error {
     use thiserror::Error as ThisError;
 
     use crate::{
-        parser::Span, read_queries::ModuleInfo, type_registrar::error::Error as PostgresTypeError,
-        utils::db_err, validation::error::Error as ValidationError,
+        parser::{Span, SqlMap},
+        read_queries::ModuleInfo,
+        type_registrar::error::Error as PostgresTypeError,
+        utils::db_err,
+        validation::error::Error as ValidationError,
     };
 
     #[derive(Debug, ThisError, Diagnostic)]
@@ -479,16 +633,18 @@ pub(crate) mod error {
         pub(crate) fn new_db_err(
             err: &postgres::Error,
             module_info: &ModuleInfo,
-            query_span: &SourceSpan,
+            sql_map: &SqlMap,
             query_name: &Span<String>,
         ) -> Self {
             let msg = format!("{err:#}");
             if let Some((position, msg, help)) = db_err(err) {
+                // The error may be located in an included fragment
+                let (info, offset) = sql_map.source(position as usize - 1);
                 Self::Db {
                     msg,
                     help,
-                    src: module_info.into(),
-                    err_span: Some((query_span.offset() + position as usize - 1).into()),
+                    src: info.unwrap_or(module_info).into(),
+                    err_span: Some(offset.into()),
                 }
             } else {
                 Self::Db {
@@ -7,6 +7,9 @@ use miette::NamedSource;
 
 use self::error::Error;
 
+/// Name of the module whose type annotations are shared by all modules (`_types.sql`)
+pub(crate) const SHARED_TYPES_MODULE: &str = "_types";
+
 #[derive(Debug, Clone)]
 pub(crate) struct ModuleInfo {
     pub(crate) path: PathBuf,
@@ -1,7 +1,10 @@
 use std::collections::BTreeMap;
 
 use crate::{
-    parser::{Module, NullableIdent, Query, QueryDataStruct, Span, TypeAnnotation},
+    parser::{
+        Attributes, Cardinality, Expect, Module, NullableIdent, Query, QueryDataStruct, Span,
+        TypeAnnotation,
+    },
     prepare_queries::{PreparedField, PreparedModule},
     read_queries::ModuleInfo,
     utils::{find_duplicate, STRICT_KEYWORD},
@@ -151,6 +154,44 @@ pub(crate) fn nullable_param_name(
     Ok(())
 }
 
+/// Columns are mapped by name onto the fields of an external row struct
+pub(crate) fn external_row_fields(
+    info: &ModuleInfo,
+    row: &QueryDataStruct,
+    stmt_cols: &[Column],
+) -> Result<(), Box<Error>> {
+    let Some(name) = row.name.as_ref().filter(|_| row.is_external()) else {
+        return Ok(());
+    };
+    for col in stmt_cols {
+        let is_field = col
+            .name()
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && !col.name().starts_with(|c: char| c.is_ascii_digit());
+        if !is_field {
+            return Err(Box::new(Error::ExternalRowColumn {
+                src: info.into(),
+                name: name.value.clone(),
+                column: col.name().to_string(),
+                pos: name.span,
+            }));
+        }
+        // Declared fields must cover every column
+        if let Some(fields) = &row.idents {
+            if fields.iter().all(|it| it.name.value != col.name()) {
+                return Err(Box::new(Error::ExternalRowField {
+                    src: info.into(),
+                    name: name.value.clone(),
+                    column: col.name().to_string(),
+                    pos: row.span,
+                }));
+            }
+        }
+    }
+    Ok(())
+}
+
 pub(crate) fn row_on_execute(
     info: &ModuleInfo,
     name: &Span<String>,
@@ -169,6 +210,125 @@ pub(crate) fn row_on_execute(
     Ok(())
 }
 
+pub(crate) fn cardinality(
+    info: &ModuleInfo,
+    name: &Span<String>,
+    query: &SourceSpan,
+    cardinality: &Option<Span<Cardinality>>,
+    row: &QueryDataStruct,
+    columns: &[Column],
+) -> Result<(), Box<Error>> {
+    if let Some(cardinality) = cardinality {
+        if cardinality.value == Cardinality::ExecRows {
+            if !row.is_empty() {
+                return Err(Box::new(Error::RowOnExecRows {
+                    src: info.into(),
+                    name: name.value.clone(),
+                    row: row.span,
+                    cardinality: cardinality.span,
+                }));
+            }
+        } else if columns.is_empty() {
+            return Err(Box::new(Error::CardinalityOnExecute {
+                src: info.into(),
+                name: name.value.clone(),
+                cardinality: cardinality.value.to_string(),
+                pos: cardinality.span,
+                query: *query,
+            }));
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn expect_on_rows(
+    info: &ModuleInfo,
+    name: &Span<String>,
+    query: &SourceSpan,
+    expect: &Option<Span<Expect>>,
+    cardinality: &Option<Span<Cardinality>>,
+    columns: &[Column],
+) -> Result<(), Box<Error>> {
+    let exec_rows = cardinality
+        .as_ref()
+        .map_or(false, |it| it.value == Cardinality::ExecRows);
+    if let Some(expect) = expect {
+        if !columns.is_empty() && !exec_rows {
+            return Err(Box::new(Error::ExpectOnRows {
+                src: info.into(),
+                name: name.value.clone(),
+                expect: expect.span,
+                query: *query,
+            }));
+        }
+    }
+    Ok(())
+}
+
+/// Check bind style consistency and name positional parameters from the annotation
+pub(crate) fn positional_params(
+    info: &ModuleInfo,
+    bind_params: &[Span<String>],
+    positional_params: &[Span<usize>],
+    fields: &[NullableIdent],
+) -> Result<Vec<Span<String>>, Box<Error>> {
+    let named_positions = fields.iter().filter_map(|it| it.position.as_ref());
+    if let Some(named) = bind_params.first() {
+        // Positional parameters in the query or named in the annotation
+        if let Some(positional) = positional_params.first().or(named_positions.clone().next()) {
+            return Err(Box::new(Error::MixedBindStyle {
+                src: info.into(),
+                named: named.span,
+                positional: positional.span,
+            }));
+        }
+        return Ok(bind_params.to_vec());
+    }
+    for (i, position) in named_positions.clone().enumerate() {
+        if let Some(prev) = named_positions.clone().take(i).find(|it| **it == *position) {
+            return Err(Box::new(Error::DuplicatePositionalName {
+                src: info.into(),
+                position: position.value,
+                first: prev.span,
+                second: position.span,
+            }));
+        }
+        if !positional_params.contains(position) {
+            return Err(Box::new(Error::UnknownPosition {
+                src: info.into(),
+                position: position.value,
+                pos: position.span,
+            }));
+        }
+    }
+    positional_params
+        .iter()
+        .map(|position| {
+            fields
+                .iter()
+                .find(|it| it.position.as_ref() == Some(position))
+                .map(|it| it.name.clone())
+                .ok_or_else(|| {
+                    Box::new(Error::UnnamedPositionalParam {
+                        src: info.into(),
+                        position: position.value,
+                        pos: position.span,
+                    })
+                })
+        })
+        .collect()
+}
+
+fn position_outside_params(info: &ModuleInfo, fields: &[NullableIdent]) -> Result<(), Box<Error>> {
+    if let Some(position) = fields.iter().find_map(|it| it.position.as_ref()) {
+        return Err(Box::new(Error::PositionOutsideParams {
+            src: info.into(),
+            pos: position.span,
+        }));
+    }
+    Ok(())
+}
+
 pub(crate) fn param_on_simple_query(
     info: &ModuleInfo,
     name: &Span<String>,
@@ -187,6 +347,19 @@ pub(crate) fn param_on_simple_query(
     Ok(())
 }
 
+fn unsupported_visibility(info: &ModuleInfo, attributes: &Attributes) -> Result<(), Box<Error>> {
+    if let Some(vis) = &attributes.vis {
+        if vis.value != "pub" && vis.value != "pub(crate)" {
+            return Err(Box::new(Error::UnsupportedVisibility {
+                src: info.into(),
+                vis: vis.value.clone(),
+                pos: vis.span,
+            }));
+        }
+    }
+    Ok(())
+}
+
 fn reserved_type_keyword(info: &ModuleInfo, s: &Span<String>) -> Result<(), Box<Error>> {
     if let Ok(it) = STRICT_KEYWORD.binary_search(&s.value.as_str()) {
         return Err(Box::new(Error::TypeRustKeyword {
@@ -266,6 +439,72 @@ pub(crate) fn named_struct_field(
     Ok(())
 }
 
+/// A shared struct must have the same fields in every module using it
+pub(crate) fn shared_struct_field(
+    info: &ModuleInfo,
+    name: &Span<String>,
+    fields: &[PreparedField],
+    shared_fields: &[PreparedField],
+) -> Result<(), Box<Error>> {
+    let label = if let Some((field, shared_field)) = fields.iter().find_map(|f| {
+        shared_fields
+            .iter()
+            .find_map(|s| (f.ident == s.ident && f.ty != s.ty).then_some((f, s)))
+    }) {
+        Some(format!(
+            "column `{}` has type `{}` here but `{}` in another module",
+            field.ident.db,
+            field.ty.pg_ty(),
+            shared_field.ty.pg_ty()
+        ))
+    } else if let Some(field) = fields.iter().find(|f| !shared_fields.contains(f)) {
+        Some(format!(
+            "column `{}` is not used by another module",
+            field.ident.db
+        ))
+    } else {
+        shared_fields
+            .iter()
+            .find(|f| !fields.contains(f))
+            .map(|field| {
+                format!(
+                    "column `{}` used by another module not found",
+                    field.ident.db
+                )
+            })
+    };
+    if let Some(label) = label {
+        return Err(Box::new(Error::IncompatibleSharedType {
+            src: info.into(),
+            name: name.value.clone(),
+            pos: name.span,
+            label,
+        }));
+    }
+    Ok(())
+}
+
+/// The shared types module only declares types
+pub(crate) fn validate_shared_module(
+    shared: &Module,
+    modules: &[Module],
+) -> Result<(), Box<Error>> {
+    validate_module(shared, &[])?;
+    if let Some(query) = shared.queries.first() {
+        return Err(Box::new(Error::QueryInSharedModule {
+            src: (&shared.info).into(),
+            name: query.name.value.clone(),
+            pos: query.name.span,
+        }));
+    }
+    if let Some(module) = modules.iter().find(|it| it.info.name == "shared") {
+        return Err(Box::new(Error::SharedModuleName {
+            src: (&module.info).into(),
+        }));
+    }
+    Ok(())
+}
+
 pub(crate) fn validate_preparation(module: &PreparedModule) -> Result<(), Box<Error>> {
     // Check generated name clash
     let mut name_registrar = BTreeMap::new();
@@ -301,7 +540,7 @@ pub(crate) fn validate_preparation(module: &PreparedModule) -> Result<(), Box<Er
     }
     for (origin, row) in &module.rows {
         reserved_type_keyword(&module.info, origin)?;
-        if row.is_named {
+        if row.is_named && !row.is_external {
             check_name(row.name.value.clone(), origin.span, "row")?;
             for field in &row.fields {
                 reserved_name_keyword(&module.info, &field.ident.db, &origin.span, "row")?;
@@ -311,12 +550,13 @@ pub(crate) fn validate_preparation(module: &PreparedModule) -> Result<(), Box<Er
                 check_name(format!("{}Borrowed", row.name), origin.span, "borrowed row")?;
             };
         }
-        check_name(format!("{}Query", row.name), origin.span, "query")?;
+        check_name(format!("{}Query", row.type_name()), origin.span, "query")?;
     }
     for (origin, params) in &module.params {
         reserved_type_keyword(&module.info, origin)?;
         if params.is_named {
             check_name(params.name.value.clone(), origin.span, "params")?;
+            check_name(format!("{}Owned", params.name), origin.span, "owned params")?;
             for field in &params.fields {
                 reserved_name_keyword(&module.info, &field.ident.db, &origin.span, "param")?;
             }
@@ -330,23 +570,40 @@ pub(crate) fn validate_module(
         info,
         types,
         queries,
+        ..
     }: &Module,
+    shared_types: &[TypeAnnotation],
 ) -> Result<(), Box<Error>> {
+    let known_types: Vec<_> = types.iter().chain(shared_types).cloned().collect();
     query_name_already_used(info, queries)?;
     named_type_already_used(info, types)?;
     for ty in types {
         duplicate_nullable_ident(info, &ty.fields)?;
+        position_outside_params(info, &ty.fields)?;
+        unsupported_visibility(info, &ty.attributes)?;
     }
     for query in queries {
+        unsupported_visibility(info, &query.attributes)?;
+        if let Some(idents) = &query.row.idents {
+            position_outside_params(info, idents)?;
+        }
         for (it, ty) in [(&query.param, "param"), (&query.row, "row")] {
             if let Some(idents) = &it.idents {
                 duplicate_nullable_ident(info, idents)?;
             };
             if let Some(name) = &it.name {
-                if it.inlined() {
+                if it.is_external() {
+                    if ty == "param" {
+                        return Err(Box::new(Error::ExternalParams {
+                            src: info.into(),
+                            name: name.value.clone(),
+                            pos: name.span,
+                        }));
+                    }
+                } else if it.inlined() {
                     inline_conflict_declared(info, name, types, ty)?;
                 } else {
-                    reference_unknown_type(info, name, types, ty)?;
+                    reference_unknown_type(info, name, &known_types, ty)?;
                 }
             }
         }
@@ -404,6 +661,37 @@ pub mod error {
             #[label("unknown named {ty}")]
             pos: SourceSpan,
         },
+        #[error("external struct `{name}` can't be used as params")]
+        #[diagnostic(help(
+            "external structs can only be used for rows, declare a params struct instead"
+        ))]
+        ExternalParams {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("path to an external struct")]
+            pos: SourceSpan,
+        },
+        #[error("column `{column}` can't be mapped to a field of `{name}`")]
+        #[diagnostic(help("rename the column using an `AS` clause with a valid rust field name"))]
+        ExternalRowColumn {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            column: String,
+            #[label("column `{column}` is not a valid field name")]
+            pos: SourceSpan,
+        },
+        #[error("column `{column}` is not a field of `{name}`")]
+        #[diagnostic(help("add `{column}` to the declared fields or remove it from the query"))]
+        ExternalRowField {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            column: String,
+            #[label("declared fields don't include `{column}`")]
+            pos: SourceSpan,
+        },
         #[error("unknown field")]
         #[diagnostic(help("use one of those names: {known}"))]
         UnknownFieldName {
@@ -437,6 +725,114 @@ pub mod error {
             #[label("but query return nothing")]
             query: SourceSpan,
         },
+        #[error("the query `{name}` declares a row cardinality but return nothing")]
+        #[diagnostic(help("use `:exec_rows` or remove the cardinality marker"))]
+        CardinalityOnExecute {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            cardinality: String,
+            #[label("`{cardinality}` declared here")]
+            pos: SourceSpan,
+            #[label("but query return nothing")]
+            query: SourceSpan,
+        },
+        #[error("the query `{name}` declares a row but only counts affected rows")]
+        #[diagnostic(help("remove row declaration or `:exec_rows` marker"))]
+        RowOnExecRows {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("row declared here")]
+            row: SourceSpan,
+            #[label("but `:exec_rows` discards it")]
+            cardinality: SourceSpan,
+        },
+        #[error("the query `{name}` expects a number of affected rows but return rows")]
+        #[diagnostic(help("use `:exec_rows` to only count rows or remove `!expect`"))]
+        ExpectOnRows {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("affected rows expected here")]
+            expect: SourceSpan,
+            #[label("but query return rows")]
+            query: SourceSpan,
+        },
+        #[error("positional and named parameters are mixed")]
+        #[diagnostic(help(
+            "use either `$N` placeholders named in the annotation or named parameters"
+        ))]
+        MixedBindStyle {
+            #[source_code]
+            src: NamedSource,
+            #[label("named parameter used here")]
+            named: SourceSpan,
+            #[label("positional parameter used here")]
+            positional: SourceSpan,
+        },
+        #[error("the positional parameter `${position}` has no name")]
+        #[diagnostic(help("name it in the query annotation using `(name = ${position})`"))]
+        UnnamedPositionalParam {
+            #[source_code]
+            src: NamedSource,
+            position: usize,
+            #[label("unnamed positional parameter")]
+            pos: SourceSpan,
+        },
+        #[error("the positional parameter `${position}` is named multiple time")]
+        #[diagnostic(help("remove one of the two names"))]
+        DuplicatePositionalName {
+            #[source_code]
+            src: NamedSource,
+            position: usize,
+            #[label("previous name")]
+            first: SourceSpan,
+            #[label("renamed here")]
+            second: SourceSpan,
+        },
+        #[error("unknown positional parameter `${position}`")]
+        #[diagnostic(help("the query must use this placeholder"))]
+        UnknownPosition {
+            #[source_code]
+            src: NamedSource,
+            position: usize,
+            #[label("no placeholder with this position was found")]
+            pos: SourceSpan,
+        },
+        #[error("positional parameters can only be named in query parameters")]
+        #[diagnostic(help("remove the position"))]
+        PositionOutsideParams {
+            #[source_code]
+            src: NamedSource,
+            #[label("position declared here")]
+            pos: SourceSpan,
+        },
+        #[error("the shared type `{name}` has incompatible fields")]
+        #[diagnostic(help("shared types are generated once, every query using `{name}` must have the same columns"))]
+        IncompatibleSharedType {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("{label}")]
+            pos: SourceSpan,
+            label: String,
+        },
+        #[error("the query `{name}` is declared in the shared types module")]
+        #[diagnostic(help("move this query to another module"))]
+        QueryInSharedModule {
+            #[source_code]
+            src: NamedSource,
+            name: String,
+            #[label("shared types module can only declare types")]
+            pos: SourceSpan,
+        },
+        #[error("the module name `shared` is reserved for shared types")]
+        #[diagnostic(help("rename this query file"))]
+        SharedModuleName {
+            #[source_code]
+            src: NamedSource,
+        },
         #[error("the query `{name}` declares a parameter but has no binding")]
         #[diagnostic(help("remove parameter declaration"))]
         ParamsOnSimpleQuery {
@@ -461,6 +857,15 @@ pub mod error {
             #[label("redefined as {second_ty} here")]
             second: SourceSpan,
         },
+        #[error("unsupported visibility `{vis}`")]
+        #[diagnostic(help("use `pub` or `pub(crate)`"))]
+        UnsupportedVisibility {
+            #[source_code]
+            src: NamedSource,
+            vis: String,
+            #[label("generated items can only be public or crate visible")]
+            pos: SourceSpan,
+        },
         #[error("`{name}` is a reserved rust keyword that cannot be escaped")]
         #[diagnostic(help("use a different name"))]
         TypeRustKeyword {
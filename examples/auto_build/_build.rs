@@ -1,4 +1,4 @@
-use cornucopia::{CodegenSettings, Error};
+use cornucopia::{CodegenSettings, ContainerConfig, Error};
 
 // This script will generate a new cornucopia file every time your schema or queries change.
 // In this example, we generate the module in our project, but
@@ -19,7 +19,7 @@ fn main() -> Result<(), Error> {
         queries_path,
         &[schema_file],
         Some(destination),
-        false,
+        &ContainerConfig::default(),
         settings,
     )?;
 
@@ -18,7 +18,7 @@ pub mod queries {
             client: &'a C,
             params: [&'a (dyn postgres_types::ToSql + Sync); N],
             stmt: &'a mut cornucopia_async::private::Stmt,
-            extractor: fn(&tokio_postgres::Row) -> &str,
+            extractor: fn(&tokio_postgres::Row) -> Result<&str, tokio_postgres::Error>,
             mapper: fn(&str) -> T,
         }
         impl<'a, C, T: 'a, const N: usize> StringQuery<'a, C, T, N>
@@ -34,21 +34,45 @@ pub mod queries {
                     mapper,
                 }
             }
+            pub fn map_with<R, F: FnMut(&str) -> R>(
+                self,
+                mut mapper: F,
+            ) -> cornucopia_async::MappedQuery<
+                'a,
+                C,
+                impl FnMut(&tokio_postgres::Row) -> Result<R, tokio_postgres::Error>,
+                N,
+            > {
+                self.try_map(move |it| Ok(mapper(it)))
+            }
+            pub fn try_map<R, E: From<tokio_postgres::Error>, F: FnMut(&str) -> Result<R, E>>(
+                self,
+                mut mapper: F,
+            ) -> cornucopia_async::MappedQuery<
+                'a,
+                C,
+                impl FnMut(&tokio_postgres::Row) -> Result<R, E>,
+                N,
+            > {
+                let extractor = self.extractor;
+                cornucopia_async::MappedQuery::new(
+                    self.client,
+                    self.params,
+                    self.stmt,
+                    move |row| mapper(extractor(row)?),
+                )
+            }
             pub async fn one(self) -> Result<T, tokio_postgres::Error> {
-                let stmt = self.stmt.prepare(self.client).await?;
-                let row = self.client.query_one(stmt, &self.params).await?;
-                Ok((self.mapper)((self.extractor)(&row)))
+                let mapper = self.mapper;
+                self.map_with(mapper).one().await
             }
             pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error> {
-                self.iter().await?.try_collect().await
+                let mapper = self.mapper;
+                self.map_with(mapper).all().await
             }
             pub async fn opt(self) -> Result<Option<T>, tokio_postgres::Error> {
-                let stmt = self.stmt.prepare(self.client).await?;
-                Ok(self
-                    .client
-                    .query_opt(stmt, &self.params)
-                    .await?
-                    .map(|row| (self.mapper)((self.extractor)(&row))))
+                let mapper = self.mapper;
+                self.map_with(mapper).opt().await
             }
             pub async fn iter(
                 self,
@@ -56,22 +80,17 @@ pub mod queries {
                 impl futures::Stream<Item = Result<T, tokio_postgres::Error>> + 'a,
                 tokio_postgres::Error,
             > {
-                let stmt = self.stmt.prepare(self.client).await?;
-                let it = self
-                    .client
-                    .query_raw(stmt, cornucopia_async::private::slice_iter(&self.params))
-                    .await?
-                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))))
-                    .into_stream();
-                Ok(it)
+                let mapper = self.mapper;
+                self.map_with(mapper).iter().await
             }
         }
         pub fn example_query() -> ExampleQueryStmt {
-            ExampleQueryStmt(cornucopia_async::private::Stmt::new(
+            ExampleQueryStmt(cornucopia_async::private::Stmt::named(
                 "SELECT
     *
 FROM
     example_table",
+                "module_1.example_query",
             ))
         }
         pub struct ExampleQueryStmt(cornucopia_async::private::Stmt);
@@ -84,7 +103,7 @@ FROM
                     client,
                     params: [],
                     stmt: &mut self.0,
-                    extractor: |row| row.get(0),
+                    extractor: |row| row.try_get(0),
                     mapper: |it| it.into(),
                 }
             }
@@ -88,7 +88,7 @@ pub mod types {
                 }
             }
         }
-        #[derive(Debug, postgres_types :: FromSql, Clone, PartialEq)]
+        #[derive(Debug, postgres_types::FromSql, Clone, PartialEq)]
         #[postgres(name = "voiceactor")]
         pub struct Voiceactor {
             #[postgres(name = "name")]
@@ -201,6 +201,69 @@ pub mod types {
                 postgres_types::__to_sql_checked(self, ty, out)
             }
         }
+        impl<'a> postgres_types::ToSql for Voiceactor {
+            fn to_sql(
+                &self,
+                ty: &postgres_types::Type,
+                out: &mut postgres_types::private::BytesMut,
+            ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>>
+            {
+                let Voiceactor { name, age } = self;
+                let fields = match *ty.kind() {
+                    postgres_types::Kind::Composite(ref fields) => fields,
+                    _ => unreachable!(),
+                };
+                out.extend_from_slice(&(fields.len() as i32).to_be_bytes());
+                for field in fields {
+                    out.extend_from_slice(&field.type_().oid().to_be_bytes());
+                    let base = out.len();
+                    out.extend_from_slice(&[0; 4]);
+                    let r = match field.name() {
+                        "name" => postgres_types::ToSql::to_sql(name, field.type_(), out),
+                        "age" => postgres_types::ToSql::to_sql(age, field.type_(), out),
+                        _ => unreachable!(),
+                    };
+                    let count = match r? {
+                        postgres_types::IsNull::Yes => -1,
+                        postgres_types::IsNull::No => {
+                            let len = out.len() - base - 4;
+                            if len > i32::max_value() as usize {
+                                return Err(Into::into("value too large to transmit"));
+                            }
+                            len as i32
+                        }
+                    };
+                    out[base..base + 4].copy_from_slice(&count.to_be_bytes());
+                }
+                Ok(postgres_types::IsNull::No)
+            }
+            fn accepts(ty: &postgres_types::Type) -> bool {
+                if ty.name() != "voiceactor" {
+                    return false;
+                }
+                match *ty.kind() {
+                    postgres_types::Kind::Composite(ref fields) => {
+                        if fields.len() != 2 {
+                            return false;
+                        }
+                        fields.iter().all(|f| match f.name() {
+                            "name" => <&'a str as postgres_types::ToSql>::accepts(f.type_()),
+                            "age" => <i32 as postgres_types::ToSql>::accepts(f.type_()),
+                            _ => false,
+                        })
+                    }
+                    _ => false,
+                }
+            }
+            fn to_sql_checked(
+                &self,
+                ty: &postgres_types::Type,
+                out: &mut postgres_types::private::BytesMut,
+            ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>>
+            {
+                postgres_types::__to_sql_checked(self, ty, out)
+            }
+        }
     }
 }
 #[allow(clippy::all, clippy::pedantic)]
@@ -213,9 +276,10 @@ pub mod queries {
         use futures;
         use futures::{StreamExt, TryStreamExt};
         pub fn insert_book() -> InsertBookStmt {
-            InsertBookStmt(cornucopia_async::private::Stmt::new(
+            InsertBookStmt(cornucopia_async::private::Stmt::named(
                 "INSERT INTO Book (title)
   VALUES ($1)",
+                "module_1.insert_book",
             ))
         }
         pub struct InsertBookStmt(cornucopia_async::private::Stmt);
@@ -225,8 +289,7 @@ pub mod queries {
                 client: &'a C,
                 title: &'a T1,
             ) -> Result<u64, tokio_postgres::Error> {
-                let stmt = self.0.prepare(client).await?;
-                client.execute(stmt, &[title]).await
+                cornucopia_async::private::execute(client, &mut self.0, &[title]).await
             }
         }
     }
@@ -235,6 +298,10 @@ pub mod queries {
         pub struct AuthorNameStartingWithParams<T1: cornucopia_async::StringSql> {
             pub start_str: T1,
         }
+        #[derive(Debug, Clone)]
+        pub struct AuthorNameStartingWithParamsOwned {
+            pub start_str: String,
+        }
         #[derive(Debug, Clone, PartialEq)]
         pub struct Authors {
             pub id: i32,
@@ -255,6 +322,23 @@ pub mod queries {
                 }
             }
         }
+        impl<'a> cornucopia_async::FromRowBorrowed<'a> for AuthorsBorrowed<'a> {
+            fn from_row_borrowed(
+                row: &'a tokio_postgres::Row,
+            ) -> Result<Self, tokio_postgres::Error> {
+                Ok(Self {
+                    id: row.try_get("id")?,
+                    name: row.try_get("name")?,
+                    country: row.try_get("country")?,
+                })
+            }
+        }
+        impl cornucopia_async::FromRow for Authors {
+            fn from_row(row: &tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+                <AuthorsBorrowed as cornucopia_async::FromRowBorrowed>::from_row_borrowed(row)
+                    .map(Into::into)
+            }
+        }
         #[derive(Debug, Clone, PartialEq)]
         pub struct AuthorNameStartingWith {
             pub authorid: i32,
@@ -285,6 +369,24 @@ pub mod queries {
                 }
             }
         }
+        impl<'a> cornucopia_async::FromRowBorrowed<'a> for AuthorNameStartingWithBorrowed<'a> {
+            fn from_row_borrowed(
+                row: &'a tokio_postgres::Row,
+            ) -> Result<Self, tokio_postgres::Error> {
+                Ok(Self {
+                    authorid: row.try_get("authorid")?,
+                    name: row.try_get("name")?,
+                    bookid: row.try_get("bookid")?,
+                    title: row.try_get("title")?,
+                })
+            }
+        }
+        impl cornucopia_async::FromRow for AuthorNameStartingWith {
+            fn from_row(row: &tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+                < AuthorNameStartingWithBorrowed as cornucopia_async :: FromRowBorrowed > ::
+        from_row_borrowed(row).map(Into :: into)
+            }
+        }
         #[derive(Debug, Clone, PartialEq)]
         pub struct SelectTranslations {
             pub title: String,
@@ -307,6 +409,22 @@ pub mod queries {
                 }
             }
         }
+        impl<'a> cornucopia_async::FromRowBorrowed<'a> for SelectTranslationsBorrowed<'a> {
+            fn from_row_borrowed(
+                row: &'a tokio_postgres::Row,
+            ) -> Result<Self, tokio_postgres::Error> {
+                Ok(Self {
+                    title: row.try_get("title")?,
+                    translations: row.try_get("translations")?,
+                })
+            }
+        }
+        impl cornucopia_async::FromRow for SelectTranslations {
+            fn from_row(row: &tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+                < SelectTranslationsBorrowed as cornucopia_async :: FromRowBorrowed > ::
+        from_row_borrowed(row).map(Into :: into)
+            }
+        }
         use cornucopia_async::GenericClient;
         use futures;
         use futures::{StreamExt, TryStreamExt};
@@ -314,7 +432,7 @@ pub mod queries {
             client: &'a C,
             params: [&'a (dyn postgres_types::ToSql + Sync); N],
             stmt: &'a mut cornucopia_async::private::Stmt,
-            extractor: fn(&tokio_postgres::Row) -> AuthorsBorrowed,
+            extractor: fn(&tokio_postgres::Row) -> Result<AuthorsBorrowed, tokio_postgres::Error>,
             mapper: fn(AuthorsBorrowed) -> T,
         }
         impl<'a, C, T: 'a, const N: usize> AuthorsQuery<'a, C, T, N>
@@ -330,21 +448,49 @@ pub mod queries {
                     mapper,
                 }
             }
+            pub fn map_with<R, F: FnMut(AuthorsBorrowed) -> R>(
+                self,
+                mut mapper: F,
+            ) -> cornucopia_async::MappedQuery<
+                'a,
+                C,
+                impl FnMut(&tokio_postgres::Row) -> Result<R, tokio_postgres::Error>,
+                N,
+            > {
+                self.try_map(move |it| Ok(mapper(it)))
+            }
+            pub fn try_map<
+                R,
+                E: From<tokio_postgres::Error>,
+                F: FnMut(AuthorsBorrowed) -> Result<R, E>,
+            >(
+                self,
+                mut mapper: F,
+            ) -> cornucopia_async::MappedQuery<
+                'a,
+                C,
+                impl FnMut(&tokio_postgres::Row) -> Result<R, E>,
+                N,
+            > {
+                let extractor = self.extractor;
+                cornucopia_async::MappedQuery::new(
+                    self.client,
+                    self.params,
+                    self.stmt,
+                    move |row| mapper(extractor(row)?),
+                )
+            }
             pub async fn one(self) -> Result<T, tokio_postgres::Error> {
-                let stmt = self.stmt.prepare(self.client).await?;
-                let row = self.client.query_one(stmt, &self.params).await?;
-                Ok((self.mapper)((self.extractor)(&row)))
+                let mapper = self.mapper;
+                self.map_with(mapper).one().await
             }
             pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error> {
-                self.iter().await?.try_collect().await
+                let mapper = self.mapper;
+                self.map_with(mapper).all().await
             }
             pub async fn opt(self) -> Result<Option<T>, tokio_postgres::Error> {
-                let stmt = self.stmt.prepare(self.client).await?;
-                Ok(self
-                    .client
-                    .query_opt(stmt, &self.params)
-                    .await?
-                    .map(|row| (self.mapper)((self.extractor)(&row))))
+                let mapper = self.mapper;
+                self.map_with(mapper).opt().await
             }
             pub async fn iter(
                 self,
@@ -352,21 +498,15 @@ pub mod queries {
                 impl futures::Stream<Item = Result<T, tokio_postgres::Error>> + 'a,
                 tokio_postgres::Error,
             > {
-                let stmt = self.stmt.prepare(self.client).await?;
-                let it = self
-                    .client
-                    .query_raw(stmt, cornucopia_async::private::slice_iter(&self.params))
-                    .await?
-                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))))
-                    .into_stream();
-                Ok(it)
+                let mapper = self.mapper;
+                self.map_with(mapper).iter().await
             }
         }
         pub struct StringQuery<'a, C: GenericClient, T, const N: usize> {
             client: &'a C,
             params: [&'a (dyn postgres_types::ToSql + Sync); N],
             stmt: &'a mut cornucopia_async::private::Stmt,
-            extractor: fn(&tokio_postgres::Row) -> &str,
+            extractor: fn(&tokio_postgres::Row) -> Result<&str, tokio_postgres::Error>,
             mapper: fn(&str) -> T,
         }
         impl<'a, C, T: 'a, const N: usize> StringQuery<'a, C, T, N>
@@ -382,21 +522,45 @@ pub mod queries {
                     mapper,
                 }
             }
+            pub fn map_with<R, F: FnMut(&str) -> R>(
+                self,
+                mut mapper: F,
+            ) -> cornucopia_async::MappedQuery<
+                'a,
+                C,
+                impl FnMut(&tokio_postgres::Row) -> Result<R, tokio_postgres::Error>,
+                N,
+            > {
+                self.try_map(move |it| Ok(mapper(it)))
+            }
+            pub fn try_map<R, E: From<tokio_postgres::Error>, F: FnMut(&str) -> Result<R, E>>(
+                self,
+                mut mapper: F,
+            ) -> cornucopia_async::MappedQuery<
+                'a,
+                C,
+                impl FnMut(&tokio_postgres::Row) -> Result<R, E>,
+                N,
+            > {
+                let extractor = self.extractor;
+                cornucopia_async::MappedQuery::new(
+                    self.client,
+                    self.params,
+                    self.stmt,
+                    move |row| mapper(extractor(row)?),
+                )
+            }
             pub async fn one(self) -> Result<T, tokio_postgres::Error> {
-                let stmt = self.stmt.prepare(self.client).await?;
-                let row = self.client.query_one(stmt, &self.params).await?;
-                Ok((self.mapper)((self.extractor)(&row)))
+                let mapper = self.mapper;
+                self.map_with(mapper).one().await
             }
             pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error> {
-                self.iter().await?.try_collect().await
+                let mapper = self.mapper;
+                self.map_with(mapper).all().await
             }
             pub async fn opt(self) -> Result<Option<T>, tokio_postgres::Error> {
-                let stmt = self.stmt.prepare(self.client).await?;
-                Ok(self
-                    .client
-                    .query_opt(stmt, &self.params)
-                    .await?
-                    .map(|row| (self.mapper)((self.extractor)(&row))))
+                let mapper = self.mapper;
+                self.map_with(mapper).opt().await
             }
             pub async fn iter(
                 self,
@@ -404,21 +568,18 @@ pub mod queries {
                 impl futures::Stream<Item = Result<T, tokio_postgres::Error>> + 'a,
                 tokio_postgres::Error,
             > {
-                let stmt = self.stmt.prepare(self.client).await?;
-                let it = self
-                    .client
-                    .query_raw(stmt, cornucopia_async::private::slice_iter(&self.params))
-                    .await?
-                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))))
-                    .into_stream();
-                Ok(it)
+                let mapper = self.mapper;
+                self.map_with(mapper).iter().await
             }
         }
         pub struct AuthorNameStartingWithQuery<'a, C: GenericClient, T, const N: usize> {
             client: &'a C,
             params: [&'a (dyn postgres_types::ToSql + Sync); N],
             stmt: &'a mut cornucopia_async::private::Stmt,
-            extractor: fn(&tokio_postgres::Row) -> AuthorNameStartingWithBorrowed,
+            extractor: fn(
+                &tokio_postgres::Row,
+            )
+                -> Result<AuthorNameStartingWithBorrowed, tokio_postgres::Error>,
             mapper: fn(AuthorNameStartingWithBorrowed) -> T,
         }
         impl<'a, C, T: 'a, const N: usize> AuthorNameStartingWithQuery<'a, C, T, N>
@@ -437,21 +598,49 @@ pub mod queries {
                     mapper,
                 }
             }
+            pub fn map_with<R, F: FnMut(AuthorNameStartingWithBorrowed) -> R>(
+                self,
+                mut mapper: F,
+            ) -> cornucopia_async::MappedQuery<
+                'a,
+                C,
+                impl FnMut(&tokio_postgres::Row) -> Result<R, tokio_postgres::Error>,
+                N,
+            > {
+                self.try_map(move |it| Ok(mapper(it)))
+            }
+            pub fn try_map<
+                R,
+                E: From<tokio_postgres::Error>,
+                F: FnMut(AuthorNameStartingWithBorrowed) -> Result<R, E>,
+            >(
+                self,
+                mut mapper: F,
+            ) -> cornucopia_async::MappedQuery<
+                'a,
+                C,
+                impl FnMut(&tokio_postgres::Row) -> Result<R, E>,
+                N,
+            > {
+                let extractor = self.extractor;
+                cornucopia_async::MappedQuery::new(
+                    self.client,
+                    self.params,
+                    self.stmt,
+                    move |row| mapper(extractor(row)?),
+                )
+            }
             pub async fn one(self) -> Result<T, tokio_postgres::Error> {
-                let stmt = self.stmt.prepare(self.client).await?;
-                let row = self.client.query_one(stmt, &self.params).await?;
-                Ok((self.mapper)((self.extractor)(&row)))
+                let mapper = self.mapper;
+                self.map_with(mapper).one().await
             }
             pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error> {
-                self.iter().await?.try_collect().await
+                let mapper = self.mapper;
+                self.map_with(mapper).all().await
             }
             pub async fn opt(self) -> Result<Option<T>, tokio_postgres::Error> {
-                let stmt = self.stmt.prepare(self.client).await?;
-                Ok(self
-                    .client
-                    .query_opt(stmt, &self.params)
-                    .await?
-                    .map(|row| (self.mapper)((self.extractor)(&row))))
+                let mapper = self.mapper;
+                self.map_with(mapper).opt().await
             }
             pub async fn iter(
                 self,
@@ -459,21 +648,20 @@ pub mod queries {
                 impl futures::Stream<Item = Result<T, tokio_postgres::Error>> + 'a,
                 tokio_postgres::Error,
             > {
-                let stmt = self.stmt.prepare(self.client).await?;
-                let it = self
-                    .client
-                    .query_raw(stmt, cornucopia_async::private::slice_iter(&self.params))
-                    .await?
-                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))))
-                    .into_stream();
-                Ok(it)
+                let mapper = self.mapper;
+                self.map_with(mapper).iter().await
             }
         }
         pub struct PublicVoiceactorQuery<'a, C: GenericClient, T, const N: usize> {
             client: &'a C,
             params: [&'a (dyn postgres_types::ToSql + Sync); N],
             stmt: &'a mut cornucopia_async::private::Stmt,
-            extractor: fn(&tokio_postgres::Row) -> super::super::types::public::VoiceactorBorrowed,
+            extractor: fn(
+                &tokio_postgres::Row,
+            ) -> Result<
+                super::super::types::public::VoiceactorBorrowed,
+                tokio_postgres::Error,
+            >,
             mapper: fn(super::super::types::public::VoiceactorBorrowed) -> T,
         }
         impl<'a, C, T: 'a, const N: usize> PublicVoiceactorQuery<'a, C, T, N>
@@ -492,21 +680,49 @@ pub mod queries {
                     mapper,
                 }
             }
+            pub fn map_with<R, F: FnMut(super::super::types::public::VoiceactorBorrowed) -> R>(
+                self,
+                mut mapper: F,
+            ) -> cornucopia_async::MappedQuery<
+                'a,
+                C,
+                impl FnMut(&tokio_postgres::Row) -> Result<R, tokio_postgres::Error>,
+                N,
+            > {
+                self.try_map(move |it| Ok(mapper(it)))
+            }
+            pub fn try_map<
+                R,
+                E: From<tokio_postgres::Error>,
+                F: FnMut(super::super::types::public::VoiceactorBorrowed) -> Result<R, E>,
+            >(
+                self,
+                mut mapper: F,
+            ) -> cornucopia_async::MappedQuery<
+                'a,
+                C,
+                impl FnMut(&tokio_postgres::Row) -> Result<R, E>,
+                N,
+            > {
+                let extractor = self.extractor;
+                cornucopia_async::MappedQuery::new(
+                    self.client,
+                    self.params,
+                    self.stmt,
+                    move |row| mapper(extractor(row)?),
+                )
+            }
             pub async fn one(self) -> Result<T, tokio_postgres::Error> {
-                let stmt = self.stmt.prepare(self.client).await?;
-                let row = self.client.query_one(stmt, &self.params).await?;
-                Ok((self.mapper)((self.extractor)(&row)))
+                let mapper = self.mapper;
+                self.map_with(mapper).one().await
             }
             pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error> {
-                self.iter().await?.try_collect().await
+                let mapper = self.mapper;
+                self.map_with(mapper).all().await
             }
             pub async fn opt(self) -> Result<Option<T>, tokio_postgres::Error> {
-                let stmt = self.stmt.prepare(self.client).await?;
-                Ok(self
-                    .client
-                    .query_opt(stmt, &self.params)
-                    .await?
-                    .map(|row| (self.mapper)((self.extractor)(&row))))
+                let mapper = self.mapper;
+                self.map_with(mapper).opt().await
             }
             pub async fn iter(
                 self,
@@ -514,21 +730,17 @@ pub mod queries {
                 impl futures::Stream<Item = Result<T, tokio_postgres::Error>> + 'a,
                 tokio_postgres::Error,
             > {
-                let stmt = self.stmt.prepare(self.client).await?;
-                let it = self
-                    .client
-                    .query_raw(stmt, cornucopia_async::private::slice_iter(&self.params))
-                    .await?
-                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))))
-                    .into_stream();
-                Ok(it)
+                let mapper = self.mapper;
+                self.map_with(mapper).iter().await
             }
         }
         pub struct SelectTranslationsQuery<'a, C: GenericClient, T, const N: usize> {
             client: &'a C,
             params: [&'a (dyn postgres_types::ToSql + Sync); N],
             stmt: &'a mut cornucopia_async::private::Stmt,
-            extractor: fn(&tokio_postgres::Row) -> SelectTranslationsBorrowed,
+            extractor: fn(
+                &tokio_postgres::Row,
+            ) -> Result<SelectTranslationsBorrowed, tokio_postgres::Error>,
             mapper: fn(SelectTranslationsBorrowed) -> T,
         }
         impl<'a, C, T: 'a, const N: usize> SelectTranslationsQuery<'a, C, T, N>
@@ -547,21 +759,49 @@ pub mod queries {
                     mapper,
                 }
             }
+            pub fn map_with<R, F: FnMut(SelectTranslationsBorrowed) -> R>(
+                self,
+                mut mapper: F,
+            ) -> cornucopia_async::MappedQuery<
+                'a,
+                C,
+                impl FnMut(&tokio_postgres::Row) -> Result<R, tokio_postgres::Error>,
+                N,
+            > {
+                self.try_map(move |it| Ok(mapper(it)))
+            }
+            pub fn try_map<
+                R,
+                E: From<tokio_postgres::Error>,
+                F: FnMut(SelectTranslationsBorrowed) -> Result<R, E>,
+            >(
+                self,
+                mut mapper: F,
+            ) -> cornucopia_async::MappedQuery<
+                'a,
+                C,
+                impl FnMut(&tokio_postgres::Row) -> Result<R, E>,
+                N,
+            > {
+                let extractor = self.extractor;
+                cornucopia_async::MappedQuery::new(
+                    self.client,
+                    self.params,
+                    self.stmt,
+                    move |row| mapper(extractor(row)?),
+                )
+            }
             pub async fn one(self) -> Result<T, tokio_postgres::Error> {
-                let stmt = self.stmt.prepare(self.client).await?;
-                let row = self.client.query_one(stmt, &self.params).await?;
-                Ok((self.mapper)((self.extractor)(&row)))
+                let mapper = self.mapper;
+                self.map_with(mapper).one().await
             }
             pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error> {
-                self.iter().await?.try_collect().await
+                let mapper = self.mapper;
+                self.map_with(mapper).all().await
             }
             pub async fn opt(self) -> Result<Option<T>, tokio_postgres::Error> {
-                let stmt = self.stmt.prepare(self.client).await?;
-                Ok(self
-                    .client
-                    .query_opt(stmt, &self.params)
-                    .await?
-                    .map(|row| (self.mapper)((self.extractor)(&row))))
+                let mapper = self.mapper;
+                self.map_with(mapper).opt().await
             }
             pub async fn iter(
                 self,
@@ -569,22 +809,17 @@ pub mod queries {
                 impl futures::Stream<Item = Result<T, tokio_postgres::Error>> + 'a,
                 tokio_postgres::Error,
             > {
-                let stmt = self.stmt.prepare(self.client).await?;
-                let it = self
-                    .client
-                    .query_raw(stmt, cornucopia_async::private::slice_iter(&self.params))
-                    .await?
-                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))))
-                    .into_stream();
-                Ok(it)
+                let mapper = self.mapper;
+                self.map_with(mapper).iter().await
             }
         }
         pub fn authors() -> AuthorsStmt {
-            AuthorsStmt(cornucopia_async::private::Stmt::new(
+            AuthorsStmt(cornucopia_async::private::Stmt::named(
                 "SELECT
     *
 FROM
     Author",
+                "module_2.authors",
             ))
         }
         pub struct AuthorsStmt(cornucopia_async::private::Stmt);
@@ -597,21 +832,22 @@ FROM
                     client,
                     params: [],
                     stmt: &mut self.0,
-                    extractor: |row| AuthorsBorrowed {
-                        id: row.get(0),
-                        name: row.get(1),
-                        country: row.get(2),
+                    extractor: |row| {
+                        <AuthorsBorrowed as cornucopia_async::FromRowBorrowed>::from_row_borrowed(
+                            row,
+                        )
                     },
                     mapper: |it| <Authors>::from(it),
                 }
             }
         }
         pub fn books() -> BooksStmt {
-            BooksStmt(cornucopia_async::private::Stmt::new(
+            BooksStmt(cornucopia_async::private::Stmt::named(
                 "SELECT
     Title
 FROM
     Book",
+                "module_2.books",
             ))
         }
         pub struct BooksStmt(cornucopia_async::private::Stmt);
@@ -624,19 +860,20 @@ FROM
                     client,
                     params: [],
                     stmt: &mut self.0,
-                    extractor: |row| row.get(0),
+                    extractor: |row| row.try_get(0),
                     mapper: |it| it.into(),
                 }
             }
         }
         pub fn author_name_by_id() -> AuthorNameByIdStmt {
-            AuthorNameByIdStmt(cornucopia_async::private::Stmt::new(
+            AuthorNameByIdStmt(cornucopia_async::private::Stmt::named(
                 "SELECT
     Author.Name
 FROM
     Author
 WHERE
     Author.Id = $1",
+                "module_2.author_name_by_id",
             ))
         }
         pub struct AuthorNameByIdStmt(cornucopia_async::private::Stmt);
@@ -650,13 +887,13 @@ WHERE
                     client,
                     params: [id],
                     stmt: &mut self.0,
-                    extractor: |row| row.get(0),
+                    extractor: |row| row.try_get(0),
                     mapper: |it| it.into(),
                 }
             }
         }
         pub fn author_name_starting_with() -> AuthorNameStartingWithStmt {
-            AuthorNameStartingWithStmt(cornucopia_async::private::Stmt::new(
+            AuthorNameStartingWithStmt(cornucopia_async::private::Stmt::named(
                 "SELECT
     BookAuthor.AuthorId,
     Author.Name,
@@ -668,6 +905,7 @@ FROM
     INNER JOIN Book ON Book.Id = BookAuthor.BookId
 WHERE
     Author.Name LIKE CONCAT($1::text, '%')",
+                "module_2.author_name_starting_with",
             ))
         }
         pub struct AuthorNameStartingWithStmt(cornucopia_async::private::Stmt);
@@ -681,11 +919,9 @@ WHERE
                     client,
                     params: [start_str],
                     stmt: &mut self.0,
-                    extractor: |row| AuthorNameStartingWithBorrowed {
-                        authorid: row.get(0),
-                        name: row.get(1),
-                        bookid: row.get(2),
-                        title: row.get(3),
+                    extractor: |row| {
+                        < AuthorNameStartingWithBorrowed as cornucopia_async :: FromRowBorrowed > ::
+from_row_borrowed(row)
                     },
                     mapper: |it| <AuthorNameStartingWith>::from(it),
                 }
@@ -707,14 +943,40 @@ WHERE
                 self.bind(client, &params.start_str)
             }
         }
+        impl<'a, C: GenericClient>
+            cornucopia_async::Params<
+                'a,
+                AuthorNameStartingWithParamsOwned,
+                AuthorNameStartingWithQuery<'a, C, AuthorNameStartingWith, 1>,
+                C,
+            > for AuthorNameStartingWithStmt
+        {
+            fn params(
+                &'a mut self,
+                client: &'a C,
+                params: &'a AuthorNameStartingWithParamsOwned,
+            ) -> AuthorNameStartingWithQuery<'a, C, AuthorNameStartingWith, 1> {
+                AuthorNameStartingWithQuery {
+                    client,
+                    params: [&params.start_str],
+                    stmt: &mut self.0,
+                    extractor: |row| {
+                        < AuthorNameStartingWithBorrowed as cornucopia_async :: FromRowBorrowed > ::
+from_row_borrowed(row)
+                    },
+                    mapper: |it| <AuthorNameStartingWith>::from(it),
+                }
+            }
+        }
         pub fn select_voice_actor_with_character() -> SelectVoiceActorWithCharacterStmt {
-            SelectVoiceActorWithCharacterStmt(cornucopia_async::private::Stmt::new(
+            SelectVoiceActorWithCharacterStmt(cornucopia_async::private::Stmt::named(
                 "SELECT
     voice_actor
 FROM
     SpongeBobVoiceActor
 WHERE
     character = $1",
+                "module_2.select_voice_actor_with_character",
             ))
         }
         pub struct SelectVoiceActorWithCharacterStmt(cornucopia_async::private::Stmt);
@@ -729,18 +991,19 @@ WHERE
                     client,
                     params: [spongebob_character],
                     stmt: &mut self.0,
-                    extractor: |row| row.get(0),
+                    extractor: |row| row.try_get(0),
                     mapper: |it| it.into(),
                 }
             }
         }
         pub fn select_translations() -> SelectTranslationsStmt {
-            SelectTranslationsStmt(cornucopia_async::private::Stmt::new(
+            SelectTranslationsStmt(cornucopia_async::private::Stmt::named(
                 "SELECT
     Title,
     Translations
 FROM
     Book",
+                "module_2.select_translations",
             ))
         }
         pub struct SelectTranslationsStmt(cornucopia_async::private::Stmt);
@@ -753,9 +1016,9 @@ FROM
                     client,
                     params: [],
                     stmt: &mut self.0,
-                    extractor: |row| SelectTranslationsBorrowed {
-                        title: row.get(0),
-                        translations: row.get(1),
+                    extractor: |row| {
+                        < SelectTranslationsBorrowed as cornucopia_async :: FromRowBorrowed > ::
+from_row_borrowed(row)
                     },
                     mapper: |it| <SelectTranslations>::from(it),
                 }
@@ -88,7 +88,7 @@ pub mod types {
                 }
             }
         }
-        #[derive(Debug, postgres_types :: FromSql, Clone, PartialEq)]
+        #[derive(Debug, postgres_types::FromSql, Clone, PartialEq)]
         #[postgres(name = "voiceactor")]
         pub struct Voiceactor {
             #[postgres(name = "name")]
@@ -201,6 +201,69 @@ pub mod types {
                 postgres_types::__to_sql_checked(self, ty, out)
             }
         }
+        impl<'a> postgres_types::ToSql for Voiceactor {
+            fn to_sql(
+                &self,
+                ty: &postgres_types::Type,
+                out: &mut postgres_types::private::BytesMut,
+            ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>>
+            {
+                let Voiceactor { name, age } = self;
+                let fields = match *ty.kind() {
+                    postgres_types::Kind::Composite(ref fields) => fields,
+                    _ => unreachable!(),
+                };
+                out.extend_from_slice(&(fields.len() as i32).to_be_bytes());
+                for field in fields {
+                    out.extend_from_slice(&field.type_().oid().to_be_bytes());
+                    let base = out.len();
+                    out.extend_from_slice(&[0; 4]);
+                    let r = match field.name() {
+                        "name" => postgres_types::ToSql::to_sql(name, field.type_(), out),
+                        "age" => postgres_types::ToSql::to_sql(age, field.type_(), out),
+                        _ => unreachable!(),
+                    };
+                    let count = match r? {
+                        postgres_types::IsNull::Yes => -1,
+                        postgres_types::IsNull::No => {
+                            let len = out.len() - base - 4;
+                            if len > i32::max_value() as usize {
+                                return Err(Into::into("value too large to transmit"));
+                            }
+                            len as i32
+                        }
+                    };
+                    out[base..base + 4].copy_from_slice(&count.to_be_bytes());
+                }
+                Ok(postgres_types::IsNull::No)
+            }
+            fn accepts(ty: &postgres_types::Type) -> bool {
+                if ty.name() != "voiceactor" {
+                    return false;
+                }
+                match *ty.kind() {
+                    postgres_types::Kind::Composite(ref fields) => {
+                        if fields.len() != 2 {
+                            return false;
+                        }
+                        fields.iter().all(|f| match f.name() {
+                            "name" => <&'a str as postgres_types::ToSql>::accepts(f.type_()),
+                            "age" => <i32 as postgres_types::ToSql>::accepts(f.type_()),
+                            _ => false,
+                        })
+                    }
+                    _ => false,
+                }
+            }
+            fn to_sql_checked(
+                &self,
+                ty: &postgres_types::Type,
+                out: &mut postgres_types::private::BytesMut,
+            ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>>
+            {
+                postgres_types::__to_sql_checked(self, ty, out)
+            }
+        }
     }
 }
 #[allow(clippy::all, clippy::pedantic)]
@@ -211,9 +274,10 @@ pub mod queries {
     pub mod module_1 {
         use postgres::{fallible_iterator::FallibleIterator, GenericClient};
         pub fn insert_book() -> InsertBookStmt {
-            InsertBookStmt(cornucopia_sync::private::Stmt::new(
+            InsertBookStmt(cornucopia_sync::private::Stmt::named(
                 "INSERT INTO Book (title)
   VALUES ($1)",
+                "module_1.insert_book",
             ))
         }
         pub struct InsertBookStmt(cornucopia_sync::private::Stmt);
@@ -223,8 +287,7 @@ pub mod queries {
                 client: &'a mut C,
                 title: &'a T1,
             ) -> Result<u64, postgres::Error> {
-                let stmt = self.0.prepare(client)?;
-                client.execute(stmt, &[title])
+                cornucopia_sync::private::execute(client, &mut self.0, &[title])
             }
         }
     }
@@ -233,6 +296,10 @@ pub mod queries {
         pub struct AuthorNameStartingWithParams<T1: cornucopia_sync::StringSql> {
             pub start_str: T1,
         }
+        #[derive(Debug, Clone)]
+        pub struct AuthorNameStartingWithParamsOwned {
+            pub start_str: String,
+        }
         #[derive(Debug, Clone, PartialEq)]
         pub struct Authors {
             pub id: i32,
@@ -253,6 +320,21 @@ pub mod queries {
                 }
             }
         }
+        impl<'a> cornucopia_sync::FromRowBorrowed<'a> for AuthorsBorrowed<'a> {
+            fn from_row_borrowed(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                Ok(Self {
+                    id: row.try_get("id")?,
+                    name: row.try_get("name")?,
+                    country: row.try_get("country")?,
+                })
+            }
+        }
+        impl cornucopia_sync::FromRow for Authors {
+            fn from_row(row: &postgres::Row) -> Result<Self, postgres::Error> {
+                <AuthorsBorrowed as cornucopia_sync::FromRowBorrowed>::from_row_borrowed(row)
+                    .map(Into::into)
+            }
+        }
         #[derive(Debug, Clone, PartialEq)]
         pub struct AuthorNameStartingWith {
             pub authorid: i32,
@@ -283,6 +365,22 @@ pub mod queries {
                 }
             }
         }
+        impl<'a> cornucopia_sync::FromRowBorrowed<'a> for AuthorNameStartingWithBorrowed<'a> {
+            fn from_row_borrowed(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                Ok(Self {
+                    authorid: row.try_get("authorid")?,
+                    name: row.try_get("name")?,
+                    bookid: row.try_get("bookid")?,
+                    title: row.try_get("title")?,
+                })
+            }
+        }
+        impl cornucopia_sync::FromRow for AuthorNameStartingWith {
+            fn from_row(row: &postgres::Row) -> Result<Self, postgres::Error> {
+                < AuthorNameStartingWithBorrowed as cornucopia_sync :: FromRowBorrowed > ::
+        from_row_borrowed(row).map(Into :: into)
+            }
+        }
         #[derive(Debug, Clone, PartialEq)]
         pub struct SelectTranslations {
             pub title: String,
@@ -305,12 +403,28 @@ pub mod queries {
                 }
             }
         }
+        impl<'a> cornucopia_sync::FromRowBorrowed<'a> for SelectTranslationsBorrowed<'a> {
+            fn from_row_borrowed(row: &'a postgres::Row) -> Result<Self, postgres::Error> {
+                Ok(Self {
+                    title: row.try_get("title")?,
+                    translations: row.try_get("translations")?,
+                })
+            }
+        }
+        impl cornucopia_sync::FromRow for SelectTranslations {
+            fn from_row(row: &postgres::Row) -> Result<Self, postgres::Error> {
+                <SelectTranslationsBorrowed as cornucopia_sync::FromRowBorrowed>::from_row_borrowed(
+                    row,
+                )
+                .map(Into::into)
+            }
+        }
         use postgres::{fallible_iterator::FallibleIterator, GenericClient};
         pub struct AuthorsQuery<'a, C: GenericClient, T, const N: usize> {
             client: &'a mut C,
             params: [&'a (dyn postgres_types::ToSql + Sync); N],
             stmt: &'a mut cornucopia_sync::private::Stmt,
-            extractor: fn(&postgres::Row) -> AuthorsBorrowed,
+            extractor: fn(&postgres::Row) -> Result<AuthorsBorrowed, postgres::Error>,
             mapper: fn(AuthorsBorrowed) -> T,
         }
         impl<'a, C, T: 'a, const N: usize> AuthorsQuery<'a, C, T, N>
@@ -326,39 +440,56 @@ pub mod queries {
                     mapper,
                 }
             }
+            pub fn map_with<R, F: FnMut(AuthorsBorrowed) -> R>(
+                self,
+                mut mapper: F,
+            ) -> cornucopia_sync::MappedQuery<
+                'a,
+                C,
+                impl FnMut(&postgres::Row) -> Result<R, postgres::Error>,
+                N,
+            > {
+                self.try_map(move |it| Ok(mapper(it)))
+            }
+            pub fn try_map<
+                R,
+                E: From<postgres::Error>,
+                F: FnMut(AuthorsBorrowed) -> Result<R, E>,
+            >(
+                self,
+                mut mapper: F,
+            ) -> cornucopia_sync::MappedQuery<'a, C, impl FnMut(&postgres::Row) -> Result<R, E>, N>
+            {
+                let extractor = self.extractor;
+                cornucopia_sync::MappedQuery::new(self.client, self.params, self.stmt, move |row| {
+                    mapper(extractor(row)?)
+                })
+            }
             pub fn one(self) -> Result<T, postgres::Error> {
-                let stmt = self.stmt.prepare(self.client)?;
-                let row = self.client.query_one(stmt, &self.params)?;
-                Ok((self.mapper)((self.extractor)(&row)))
+                let mapper = self.mapper;
+                self.map_with(mapper).one()
             }
             pub fn all(self) -> Result<Vec<T>, postgres::Error> {
-                self.iter()?.collect()
+                let mapper = self.mapper;
+                self.map_with(mapper).all()
             }
             pub fn opt(self) -> Result<Option<T>, postgres::Error> {
-                let stmt = self.stmt.prepare(self.client)?;
-                Ok(self
-                    .client
-                    .query_opt(stmt, &self.params)?
-                    .map(|row| (self.mapper)((self.extractor)(&row))))
+                let mapper = self.mapper;
+                self.map_with(mapper).opt()
             }
             pub fn iter(
                 self,
             ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
             {
-                let stmt = self.stmt.prepare(self.client)?;
-                let it = self
-                    .client
-                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
-                    .iterator()
-                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
-                Ok(it)
+                let mapper = self.mapper;
+                self.map_with(mapper).iter()
             }
         }
         pub struct StringQuery<'a, C: GenericClient, T, const N: usize> {
             client: &'a mut C,
             params: [&'a (dyn postgres_types::ToSql + Sync); N],
             stmt: &'a mut cornucopia_sync::private::Stmt,
-            extractor: fn(&postgres::Row) -> &str,
+            extractor: fn(&postgres::Row) -> Result<&str, postgres::Error>,
             mapper: fn(&str) -> T,
         }
         impl<'a, C, T: 'a, const N: usize> StringQuery<'a, C, T, N>
@@ -374,39 +505,53 @@ pub mod queries {
                     mapper,
                 }
             }
+            pub fn map_with<R, F: FnMut(&str) -> R>(
+                self,
+                mut mapper: F,
+            ) -> cornucopia_sync::MappedQuery<
+                'a,
+                C,
+                impl FnMut(&postgres::Row) -> Result<R, postgres::Error>,
+                N,
+            > {
+                self.try_map(move |it| Ok(mapper(it)))
+            }
+            pub fn try_map<R, E: From<postgres::Error>, F: FnMut(&str) -> Result<R, E>>(
+                self,
+                mut mapper: F,
+            ) -> cornucopia_sync::MappedQuery<'a, C, impl FnMut(&postgres::Row) -> Result<R, E>, N>
+            {
+                let extractor = self.extractor;
+                cornucopia_sync::MappedQuery::new(self.client, self.params, self.stmt, move |row| {
+                    mapper(extractor(row)?)
+                })
+            }
             pub fn one(self) -> Result<T, postgres::Error> {
-                let stmt = self.stmt.prepare(self.client)?;
-                let row = self.client.query_one(stmt, &self.params)?;
-                Ok((self.mapper)((self.extractor)(&row)))
+                let mapper = self.mapper;
+                self.map_with(mapper).one()
             }
             pub fn all(self) -> Result<Vec<T>, postgres::Error> {
-                self.iter()?.collect()
+                let mapper = self.mapper;
+                self.map_with(mapper).all()
             }
             pub fn opt(self) -> Result<Option<T>, postgres::Error> {
-                let stmt = self.stmt.prepare(self.client)?;
-                Ok(self
-                    .client
-                    .query_opt(stmt, &self.params)?
-                    .map(|row| (self.mapper)((self.extractor)(&row))))
+                let mapper = self.mapper;
+                self.map_with(mapper).opt()
             }
             pub fn iter(
                 self,
             ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
             {
-                let stmt = self.stmt.prepare(self.client)?;
-                let it = self
-                    .client
-                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
-                    .iterator()
-                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
-                Ok(it)
+                let mapper = self.mapper;
+                self.map_with(mapper).iter()
             }
         }
         pub struct AuthorNameStartingWithQuery<'a, C: GenericClient, T, const N: usize> {
             client: &'a mut C,
             params: [&'a (dyn postgres_types::ToSql + Sync); N],
             stmt: &'a mut cornucopia_sync::private::Stmt,
-            extractor: fn(&postgres::Row) -> AuthorNameStartingWithBorrowed,
+            extractor:
+                fn(&postgres::Row) -> Result<AuthorNameStartingWithBorrowed, postgres::Error>,
             mapper: fn(AuthorNameStartingWithBorrowed) -> T,
         }
         impl<'a, C, T: 'a, const N: usize> AuthorNameStartingWithQuery<'a, C, T, N>
@@ -425,39 +570,60 @@ pub mod queries {
                     mapper,
                 }
             }
+            pub fn map_with<R, F: FnMut(AuthorNameStartingWithBorrowed) -> R>(
+                self,
+                mut mapper: F,
+            ) -> cornucopia_sync::MappedQuery<
+                'a,
+                C,
+                impl FnMut(&postgres::Row) -> Result<R, postgres::Error>,
+                N,
+            > {
+                self.try_map(move |it| Ok(mapper(it)))
+            }
+            pub fn try_map<
+                R,
+                E: From<postgres::Error>,
+                F: FnMut(AuthorNameStartingWithBorrowed) -> Result<R, E>,
+            >(
+                self,
+                mut mapper: F,
+            ) -> cornucopia_sync::MappedQuery<'a, C, impl FnMut(&postgres::Row) -> Result<R, E>, N>
+            {
+                let extractor = self.extractor;
+                cornucopia_sync::MappedQuery::new(self.client, self.params, self.stmt, move |row| {
+                    mapper(extractor(row)?)
+                })
+            }
             pub fn one(self) -> Result<T, postgres::Error> {
-                let stmt = self.stmt.prepare(self.client)?;
-                let row = self.client.query_one(stmt, &self.params)?;
-                Ok((self.mapper)((self.extractor)(&row)))
+                let mapper = self.mapper;
+                self.map_with(mapper).one()
             }
             pub fn all(self) -> Result<Vec<T>, postgres::Error> {
-                self.iter()?.collect()
+                let mapper = self.mapper;
+                self.map_with(mapper).all()
             }
             pub fn opt(self) -> Result<Option<T>, postgres::Error> {
-                let stmt = self.stmt.prepare(self.client)?;
-                Ok(self
-                    .client
-                    .query_opt(stmt, &self.params)?
-                    .map(|row| (self.mapper)((self.extractor)(&row))))
+                let mapper = self.mapper;
+                self.map_with(mapper).opt()
             }
             pub fn iter(
                 self,
             ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
             {
-                let stmt = self.stmt.prepare(self.client)?;
-                let it = self
-                    .client
-                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
-                    .iterator()
-                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
-                Ok(it)
+                let mapper = self.mapper;
+                self.map_with(mapper).iter()
             }
         }
         pub struct PublicVoiceactorQuery<'a, C: GenericClient, T, const N: usize> {
             client: &'a mut C,
             params: [&'a (dyn postgres_types::ToSql + Sync); N],
             stmt: &'a mut cornucopia_sync::private::Stmt,
-            extractor: fn(&postgres::Row) -> super::super::types::public::VoiceactorBorrowed,
+            extractor:
+                fn(
+                    &postgres::Row,
+                )
+                    -> Result<super::super::types::public::VoiceactorBorrowed, postgres::Error>,
             mapper: fn(super::super::types::public::VoiceactorBorrowed) -> T,
         }
         impl<'a, C, T: 'a, const N: usize> PublicVoiceactorQuery<'a, C, T, N>
@@ -476,39 +642,56 @@ pub mod queries {
                     mapper,
                 }
             }
+            pub fn map_with<R, F: FnMut(super::super::types::public::VoiceactorBorrowed) -> R>(
+                self,
+                mut mapper: F,
+            ) -> cornucopia_sync::MappedQuery<
+                'a,
+                C,
+                impl FnMut(&postgres::Row) -> Result<R, postgres::Error>,
+                N,
+            > {
+                self.try_map(move |it| Ok(mapper(it)))
+            }
+            pub fn try_map<
+                R,
+                E: From<postgres::Error>,
+                F: FnMut(super::super::types::public::VoiceactorBorrowed) -> Result<R, E>,
+            >(
+                self,
+                mut mapper: F,
+            ) -> cornucopia_sync::MappedQuery<'a, C, impl FnMut(&postgres::Row) -> Result<R, E>, N>
+            {
+                let extractor = self.extractor;
+                cornucopia_sync::MappedQuery::new(self.client, self.params, self.stmt, move |row| {
+                    mapper(extractor(row)?)
+                })
+            }
             pub fn one(self) -> Result<T, postgres::Error> {
-                let stmt = self.stmt.prepare(self.client)?;
-                let row = self.client.query_one(stmt, &self.params)?;
-                Ok((self.mapper)((self.extractor)(&row)))
+                let mapper = self.mapper;
+                self.map_with(mapper).one()
             }
             pub fn all(self) -> Result<Vec<T>, postgres::Error> {
-                self.iter()?.collect()
+                let mapper = self.mapper;
+                self.map_with(mapper).all()
             }
             pub fn opt(self) -> Result<Option<T>, postgres::Error> {
-                let stmt = self.stmt.prepare(self.client)?;
-                Ok(self
-                    .client
-                    .query_opt(stmt, &self.params)?
-                    .map(|row| (self.mapper)((self.extractor)(&row))))
+                let mapper = self.mapper;
+                self.map_with(mapper).opt()
             }
             pub fn iter(
                 self,
             ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
             {
-                let stmt = self.stmt.prepare(self.client)?;
-                let it = self
-                    .client
-                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
-                    .iterator()
-                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
-                Ok(it)
+                let mapper = self.mapper;
+                self.map_with(mapper).iter()
             }
         }
         pub struct SelectTranslationsQuery<'a, C: GenericClient, T, const N: usize> {
             client: &'a mut C,
             params: [&'a (dyn postgres_types::ToSql + Sync); N],
             stmt: &'a mut cornucopia_sync::private::Stmt,
-            extractor: fn(&postgres::Row) -> SelectTranslationsBorrowed,
+            extractor: fn(&postgres::Row) -> Result<SelectTranslationsBorrowed, postgres::Error>,
             mapper: fn(SelectTranslationsBorrowed) -> T,
         }
         impl<'a, C, T: 'a, const N: usize> SelectTranslationsQuery<'a, C, T, N>
@@ -527,40 +710,58 @@ pub mod queries {
                     mapper,
                 }
             }
+            pub fn map_with<R, F: FnMut(SelectTranslationsBorrowed) -> R>(
+                self,
+                mut mapper: F,
+            ) -> cornucopia_sync::MappedQuery<
+                'a,
+                C,
+                impl FnMut(&postgres::Row) -> Result<R, postgres::Error>,
+                N,
+            > {
+                self.try_map(move |it| Ok(mapper(it)))
+            }
+            pub fn try_map<
+                R,
+                E: From<postgres::Error>,
+                F: FnMut(SelectTranslationsBorrowed) -> Result<R, E>,
+            >(
+                self,
+                mut mapper: F,
+            ) -> cornucopia_sync::MappedQuery<'a, C, impl FnMut(&postgres::Row) -> Result<R, E>, N>
+            {
+                let extractor = self.extractor;
+                cornucopia_sync::MappedQuery::new(self.client, self.params, self.stmt, move |row| {
+                    mapper(extractor(row)?)
+                })
+            }
             pub fn one(self) -> Result<T, postgres::Error> {
-                let stmt = self.stmt.prepare(self.client)?;
-                let row = self.client.query_one(stmt, &self.params)?;
-                Ok((self.mapper)((self.extractor)(&row)))
+                let mapper = self.mapper;
+                self.map_with(mapper).one()
             }
             pub fn all(self) -> Result<Vec<T>, postgres::Error> {
-                self.iter()?.collect()
+                let mapper = self.mapper;
+                self.map_with(mapper).all()
             }
             pub fn opt(self) -> Result<Option<T>, postgres::Error> {
-                let stmt = self.stmt.prepare(self.client)?;
-                Ok(self
-                    .client
-                    .query_opt(stmt, &self.params)?
-                    .map(|row| (self.mapper)((self.extractor)(&row))))
+                let mapper = self.mapper;
+                self.map_with(mapper).opt()
             }
             pub fn iter(
                 self,
             ) -> Result<impl Iterator<Item = Result<T, postgres::Error>> + 'a, postgres::Error>
             {
-                let stmt = self.stmt.prepare(self.client)?;
-                let it = self
-                    .client
-                    .query_raw(stmt, cornucopia_sync::private::slice_iter(&self.params))?
-                    .iterator()
-                    .map(move |res| res.map(|row| (self.mapper)((self.extractor)(&row))));
-                Ok(it)
+                let mapper = self.mapper;
+                self.map_with(mapper).iter()
             }
         }
         pub fn authors() -> AuthorsStmt {
-            AuthorsStmt(cornucopia_sync::private::Stmt::new(
+            AuthorsStmt(cornucopia_sync::private::Stmt::named(
                 "SELECT
     *
 FROM
     Author",
+                "module_2.authors",
             ))
         }
         pub struct AuthorsStmt(cornucopia_sync::private::Stmt);
@@ -573,21 +774,22 @@ FROM
                     client,
                     params: [],
                     stmt: &mut self.0,
-                    extractor: |row| AuthorsBorrowed {
-                        id: row.get(0),
-                        name: row.get(1),
-                        country: row.get(2),
+                    extractor: |row| {
+                        <AuthorsBorrowed as cornucopia_sync::FromRowBorrowed>::from_row_borrowed(
+                            row,
+                        )
                     },
                     mapper: |it| <Authors>::from(it),
                 }
             }
         }
         pub fn books() -> BooksStmt {
-            BooksStmt(cornucopia_sync::private::Stmt::new(
+            BooksStmt(cornucopia_sync::private::Stmt::named(
                 "SELECT
     Title
 FROM
     Book",
+                "module_2.books",
             ))
         }
         pub struct BooksStmt(cornucopia_sync::private::Stmt);
@@ -600,19 +802,20 @@ FROM
                     client,
                     params: [],
                     stmt: &mut self.0,
-                    extractor: |row| row.get(0),
+                    extractor: |row| row.try_get(0),
                     mapper: |it| it.into(),
                 }
             }
         }
         pub fn author_name_by_id() -> AuthorNameByIdStmt {
-            AuthorNameByIdStmt(cornucopia_sync::private::Stmt::new(
+            AuthorNameByIdStmt(cornucopia_sync::private::Stmt::named(
                 "SELECT
     Author.Name
 FROM
     Author
 WHERE
     Author.Id = $1",
+                "module_2.author_name_by_id",
             ))
         }
         pub struct AuthorNameByIdStmt(cornucopia_sync::private::Stmt);
@@ -626,13 +829,13 @@ WHERE
                     client,
                     params: [id],
                     stmt: &mut self.0,
-                    extractor: |row| row.get(0),
+                    extractor: |row| row.try_get(0),
                     mapper: |it| it.into(),
                 }
             }
         }
         pub fn author_name_starting_with() -> AuthorNameStartingWithStmt {
-            AuthorNameStartingWithStmt(cornucopia_sync::private::Stmt::new(
+            AuthorNameStartingWithStmt(cornucopia_sync::private::Stmt::named(
                 "SELECT
     BookAuthor.AuthorId,
     Author.Name,
@@ -644,6 +847,7 @@ FROM
     INNER JOIN Book ON Book.Id = BookAuthor.BookId
 WHERE
     Author.Name LIKE CONCAT($1::text, '%')",
+                "module_2.author_name_starting_with",
             ))
         }
         pub struct AuthorNameStartingWithStmt(cornucopia_sync::private::Stmt);
@@ -657,11 +861,9 @@ WHERE
                     client,
                     params: [start_str],
                     stmt: &mut self.0,
-                    extractor: |row| AuthorNameStartingWithBorrowed {
-                        authorid: row.get(0),
-                        name: row.get(1),
-                        bookid: row.get(2),
-                        title: row.get(3),
+                    extractor: |row| {
+                        < AuthorNameStartingWithBorrowed as cornucopia_sync :: FromRowBorrowed > ::
+from_row_borrowed(row)
                     },
                     mapper: |it| <AuthorNameStartingWith>::from(it),
                 }
@@ -683,14 +885,40 @@ WHERE
                 self.bind(client, &params.start_str)
             }
         }
+        impl<'a, C: GenericClient>
+            cornucopia_sync::Params<
+                'a,
+                AuthorNameStartingWithParamsOwned,
+                AuthorNameStartingWithQuery<'a, C, AuthorNameStartingWith, 1>,
+                C,
+            > for AuthorNameStartingWithStmt
+        {
+            fn params(
+                &'a mut self,
+                client: &'a mut C,
+                params: &'a AuthorNameStartingWithParamsOwned,
+            ) -> AuthorNameStartingWithQuery<'a, C, AuthorNameStartingWith, 1> {
+                AuthorNameStartingWithQuery {
+                    client,
+                    params: [&params.start_str],
+                    stmt: &mut self.0,
+                    extractor: |row| {
+                        < AuthorNameStartingWithBorrowed as cornucopia_sync :: FromRowBorrowed > ::
+from_row_borrowed(row)
+                    },
+                    mapper: |it| <AuthorNameStartingWith>::from(it),
+                }
+            }
+        }
         pub fn select_voice_actor_with_character() -> SelectVoiceActorWithCharacterStmt {
-            SelectVoiceActorWithCharacterStmt(cornucopia_sync::private::Stmt::new(
+            SelectVoiceActorWithCharacterStmt(cornucopia_sync::private::Stmt::named(
                 "SELECT
     voice_actor
 FROM
     SpongeBobVoiceActor
 WHERE
     character = $1",
+                "module_2.select_voice_actor_with_character",
             ))
         }
         pub struct SelectVoiceActorWithCharacterStmt(cornucopia_sync::private::Stmt);
@@ -705,18 +933,19 @@ WHERE
                     client,
                     params: [spongebob_character],
                     stmt: &mut self.0,
-                    extractor: |row| row.get(0),
+                    extractor: |row| row.try_get(0),
                     mapper: |it| it.into(),
                 }
             }
         }
         pub fn select_translations() -> SelectTranslationsStmt {
-            SelectTranslationsStmt(cornucopia_sync::private::Stmt::new(
+            SelectTranslationsStmt(cornucopia_sync::private::Stmt::named(
                 "SELECT
     Title,
     Translations
 FROM
     Book",
+                "module_2.select_translations",
             ))
         }
         pub struct SelectTranslationsStmt(cornucopia_sync::private::Stmt);
@@ -729,9 +958,9 @@ FROM
                     client,
                     params: [],
                     stmt: &mut self.0,
-                    extractor: |row| SelectTranslationsBorrowed {
-                        title: row.get(0),
-                        translations: row.get(1),
+                    extractor: |row| {
+                        < SelectTranslationsBorrowed as cornucopia_sync :: FromRowBorrowed > ::
+from_row_borrowed(row)
                     },
                     mapper: |it| <SelectTranslations>::from(it),
                 }
@@ -0,0 +1,2 @@
+--: NamedSummary(price?)
+--: NamedInsert(price?)
@@ -0,0 +1,15 @@
+--: #[derive(Hash, Eq)] NamedId()
+--: #[serde(rename_all = "UPPERCASE")] pub(crate) NamedShout()
+
+--! #[must_use] named_ids: NamedId
+SELECT id, show FROM named;
+--! named_shout: NamedShout
+SELECT name, show FROM named;
+--! #[derive(Hash, Eq)] #[serde(rename_all = "camelCase")] named_hashable: (id?)
+SELECT id, show AS is_shown FROM named;
+--! #[deprecated = "use `named_ids` instead"] named_deprecated
+SELECT id FROM named;
+--! #[cfg(any())] named_never_compiled
+SELECT id FROM named;
+--! pub(crate) named_crate
+SELECT name FROM named;
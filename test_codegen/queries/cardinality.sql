@@ -0,0 +1,14 @@
+--: NamedPrice(price?)
+
+--! cardinality_insert :one
+INSERT INTO named (name, price, show) VALUES (:name, :price, true) RETURNING id;
+--! cardinality_one : NamedPrice :one
+SELECT name, price FROM named WHERE id = :id;
+--! cardinality_opt : NamedPrice :opt
+SELECT name, price FROM named WHERE id = :id;
+--! cardinality_many :many
+SELECT name FROM named WHERE name = :name ORDER BY id;
+--! cardinality_exec_rows :exec_rows
+UPDATE named SET show = false WHERE name = :name RETURNING id;
+--! cardinality_summary : NamedSummary :many
+SELECT price, name FROM named WHERE name = :name ORDER BY id;
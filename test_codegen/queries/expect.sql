@@ -0,0 +1,6 @@
+--! expect_rename !expect 1
+UPDATE named SET name = :name WHERE id = :id;
+--! expect_rename_all !expect 1 rollback
+UPDATE named SET name = :new_name WHERE name = :name;
+--! expect_delete :exec_rows !expect 1
+DELETE FROM named WHERE id = :id RETURNING id;
@@ -0,0 +1,4 @@
+--! external_named_by_id : crate::model::Product :opt
+SELECT id, name, price FROM named WHERE id = :id;
+--! external_named : crate::model::Product(id, name, price)
+SELECT id, name, price FROM named ORDER BY id;
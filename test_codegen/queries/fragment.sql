@@ -0,0 +1,11 @@
+--@ fragment book_columns
+author, name;
+
+--@ fragment book_by_name
+SELECT {{book_columns}} FROM book WHERE name = :name;
+
+--! fragment_book_by_name: (author?)
+{{book_by_name}};
+
+--! fragment_literal: (author?)
+SELECT array_ndims('{{1}}'::int[][]) AS dims, $${{unknown}}$$ AS body, {{book_columns}} FROM book /* {{unknown}} */ WHERE name = :name;
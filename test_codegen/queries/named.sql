@@ -10,6 +10,10 @@ INSERT INTO named (price, name, show) VALUES (:price, :name, false) RETURNING id
 SELECT * FROM named;
 --! named_by_id: Named
 SELECT * FROM named WHERE id = :id;
+--! new_named_shared NamedInsert
+INSERT INTO named (name, price, show) VALUES (:name, :price, true);
+--! named_summary: NamedSummary
+SELECT name, price FROM named WHERE id = :id;
 
 --: named_composite(wow?,such_cool?)
 --: "named_composite.with_dot"("this.is.inconceivable"?)
@@ -11,4 +11,19 @@ SELECT * FROM book WHERE name = ANY (:title);
 UPDATE book SET name = :name WHERE length(name) > 42 AND length(:name) < 42;
 
 --! params_order
-UPDATE imaginary SET c=:c, a=:a, z=:a, r=:c;
\ No newline at end of file
+UPDATE imaginary SET c=:c, a=:a, z=:a, r=:c;
+
+--! insert_book_quoted ("book-author"?)
+INSERT INTO book (author, name) VALUES (:"book-author", :"book-name");
+
+--! select_book_positional (name = $1, author? = $2): (author?)
+SELECT * FROM book WHERE name = $1 AND author IS NOT DISTINCT FROM $2;
+
+--! select_book_columns: (author?)
+SELECT {{book_columns}} FROM book WHERE name = ANY (@names);
+
+--! select_book_sigil: (author?)
+SELECT * FROM book WHERE ARRAY[name] <@ @names AND @ -1 = 1;
+
+--! params_method_names
+UPDATE book SET name = :execute WHERE name = :bind AND length(:params) > 0;
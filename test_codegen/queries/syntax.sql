@@ -46,6 +46,12 @@ INSERT INTO syntax ("trick:y", async, enum) VALUES (e'this is ''not'' a \':bind_
 INSERT INTO syntax ("trick:y", async, enum) VALUES (E'this is \'not\' a \':bind_param\'', :async, :enum);
 --! tricky_sql10
 INSERT INTO syntax ("trick:y", async, enum) VALUES ('this is just a cast'::text, :async, :enum);
+--! tricky_sql11
+INSERT INTO syntax ("trick:y", async, enum) VALUES ($$this is not a $1 param$$, @async, @enum);
+--! tricky_sql12
+INSERT INTO syntax ("trick:y", async, enum) VALUES ('this is not an @bind_param', :"async", :"enum");
+--! tricky_sql13
+SELECT to_tsvector('simple', @text) @@to_tsquery('simple', 'word') AS matches, box '((0,0),(2,2))' @>point '(1,1)' AS contains;
 
 --! typeof
 SELECT * FROM syntax;
@@ -1,4 +1,5 @@
 mod cornucopia;
+mod model;
 
 use ::cornucopia_sync::IterSql;
 
@@ -8,7 +9,7 @@ use rust_decimal::Decimal;
 use serde_json::Value;
 use std::{
     borrow::Cow,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     net::{IpAddr, Ipv4Addr},
 };
 use time::{OffsetDateTime, PrimitiveDateTime};
@@ -16,6 +17,17 @@ use uuid::Uuid;
 
 use crate::cornucopia::{
     queries::{
+        attributes::{
+            sync::{named_crate, named_hashable, named_ids, named_shout},
+            NamedHashable, NamedId,
+        },
+        cardinality::{
+            sync::{
+                cardinality_exec_rows, cardinality_insert, cardinality_many, cardinality_one,
+                cardinality_opt, cardinality_summary,
+            },
+            NamedPrice,
+        },
         copy::sync::{insert_clone, insert_copy, select_copy},
         domain::{
             sync::{
@@ -23,18 +35,31 @@ use crate::cornucopia::{
             },
             InsertNightmareDomainParams, SelectNightmareDomain, SelectNightmareDomainNull,
         },
+        expect::sync::{expect_delete, expect_rename, expect_rename_all},
+        external::sync::{external_named, external_named_by_id},
+        fragment::{
+            sync::{fragment_book_by_name, fragment_literal},
+            FragmentBookByName, FragmentLiteral,
+        },
         named::sync::{
-            named, named_by_id, named_complex, new_named_complex, new_named_hidden,
-            new_named_visible,
+            named, named_by_id, named_complex, named_summary, new_named_complex, new_named_hidden,
+            new_named_shared, new_named_visible,
+        },
+        named::{
+            Named, NamedBorrowed, NamedComplex, NamedComplexParams, NamedComplexParamsOwned,
+            NamedParams, NamedParamsOwned,
         },
-        named::{Named, NamedComplex, NamedComplexParams, NamedParams},
         nullity::sync::{new_nullity, nullity},
         nullity::{Nullity, NullityParams},
         params::sync::insert_book,
         params::{
-            sync::{find_books, params_use_twice, select_book},
-            SelectBook,
+            sync::{
+                find_books, insert_book_quoted, params_method_names, params_use_twice, select_book,
+                select_book_columns, select_book_positional, select_book_sigil,
+            },
+            FindBooks, SelectBook, SelectBookColumns, SelectBookPositional, SelectBookSigil,
         },
+        shared::{NamedInsert, NamedSummary},
         stress::{
             sync::{
                 insert_everything, insert_everything_array, insert_nightmare, select_everything,
@@ -43,8 +68,8 @@ use crate::cornucopia::{
             Everything, EverythingArray, EverythingArrayParams, EverythingParams,
         },
         syntax::{
-            sync::{r#typeof, tricky_sql10},
-            TrickySql10Params,
+            sync::{r#typeof, tricky_sql10, tricky_sql11, tricky_sql12, tricky_sql13},
+            TrickySql10Params, TrickySql11Params, TrickySql12Params, TrickySql13,
         },
     },
     types::public::{
@@ -55,7 +80,8 @@ use crate::cornucopia::{
         SyntaxComposite, SyntaxEnum,
     },
 };
-use cornucopia_sync::Params;
+use crate::model::Product;
+use cornucopia_sync::{FromRow, FromRowBorrowed, Params, RowCountError};
 
 pub fn main() {
     let client = &mut Config::new()
@@ -69,11 +95,19 @@ pub fn main() {
     test_copy(client);
     test_params(client);
     test_named(client);
+    test_attributes(client);
+    test_derives();
+    test_cardinality(client);
+    test_expect(client);
+    test_external(client);
     test_nullity(client);
     test_stress(client);
     test_domain(client);
     test_trait_sql(client);
     test_keyword_escaping(client);
+    test_repository(client);
+    test_tracing(client);
+    test_observer(client);
 }
 
 pub fn test_params(client: &mut Client) {
@@ -103,6 +137,129 @@ pub fn test_params(client: &mut Client) {
         ]
     );
     params_use_twice().bind(client, &"name").unwrap();
+
+    // Bind syntax variants
+    insert_book_quoted()
+        .bind(client, &Some("Bram Stoker"), &"Dracula")
+        .unwrap();
+    assert_eq!(
+        select_book_positional()
+            .bind(client, &"Dracula", &Some("Bram Stoker"))
+            .one()
+            .unwrap(),
+        SelectBookPositional {
+            author: Some("Bram Stoker".into()),
+            name: "Dracula".into()
+        }
+    );
+    assert!(select_book_positional()
+        .bind(client, &"Dracula", &None::<&str>)
+        .opt()
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        select_book_sigil()
+            .bind(client, &["Dracula", "Necronomicon"].as_slice())
+            .all()
+            .unwrap(),
+        &[
+            SelectBookSigil {
+                author: None,
+                name: "Necronomicon".into()
+            },
+            SelectBookSigil {
+                author: Some("Bram Stoker".into()),
+                name: "Dracula".into()
+            }
+        ]
+    );
+
+    // Fragments included from another module
+    assert_eq!(
+        select_book_columns()
+            .bind(client, &["Dracula"].as_slice())
+            .one()
+            .unwrap(),
+        SelectBookColumns {
+            author: Some("Bram Stoker".into()),
+            name: "Dracula".into()
+        }
+    );
+    assert_eq!(
+        fragment_book_by_name()
+            .bind(client, &"Dracula")
+            .one()
+            .unwrap(),
+        FragmentBookByName {
+            author: Some("Bram Stoker".into()),
+            name: "Dracula".into()
+        }
+    );
+    // Includes are not expanded in literals and comments
+    assert_eq!(
+        fragment_literal().bind(client, &"Dracula").one().unwrap(),
+        FragmentLiteral {
+            dims: 2,
+            body: "{{unknown}}".into(),
+            author: Some("Bram Stoker".into()),
+            name: "Dracula".into()
+        }
+    );
+
+    // Builder setting params by name, nullable ones default to `None`
+    assert_eq!(
+        1,
+        insert_book()
+            .builder()
+            .name(&"The King in Yellow")
+            .execute(client)
+            .unwrap()
+    );
+    assert_eq!(
+        1,
+        insert_book()
+            .builder()
+            .author(&Some("Mary Shelley"))
+            .name(&"Frankenstein")
+            .execute(client)
+            .unwrap()
+    );
+    assert_eq!(
+        select_book_sigil()
+            .builder()
+            .names(&["The King in Yellow", "Frankenstein"].as_slice())
+            .execute(client)
+            .all()
+            .unwrap(),
+        &[
+            SelectBookSigil {
+                author: None,
+                name: "The King in Yellow".into()
+            },
+            SelectBookSigil {
+                author: Some("Mary Shelley".into()),
+                name: "Frankenstein".into()
+            }
+        ]
+    );
+
+    // Params named like generated methods
+    assert_eq!(
+        1,
+        params_method_names()
+            .bind(client, &"Frankenstein", &"Frankenstein", &"x")
+            .unwrap()
+    );
+    assert_eq!(
+        1,
+        params_method_names()
+            .builder()
+            .bind(&"Frankenstein")
+            .params(&"x")
+            .execute_(&"The Modern Prometheus")
+            .execute(client)
+            .unwrap()
+    );
 }
 
 pub fn test_trait_sql(client: &mut Client) {
@@ -246,6 +403,75 @@ pub fn test_named(client: &mut Client) {
         &[hidden_id, visible_id, last_id]
     );
 
+    // Mappers capturing their context, their error merged into the query one
+    let offset = 100;
+    assert_eq!(
+        named()
+            .bind(client)
+            .map_with(|it| it.id + offset)
+            .all()
+            .unwrap(),
+        &[hidden_id + offset, visible_id + offset, last_id + offset]
+    );
+    #[derive(Debug, PartialEq)]
+    enum MapError {
+        Db,
+        Hidden(i32),
+    }
+    impl From<postgres::Error> for MapError {
+        fn from(_: postgres::Error) -> Self {
+            Self::Db
+        }
+    }
+    let visible = |it: NamedBorrowed| {
+        if it.show {
+            Ok(it.name.to_string())
+        } else {
+            Err(MapError::Hidden(it.id))
+        }
+    };
+    assert_eq!(
+        named().bind(client).try_map(visible).all(),
+        Err(MapError::Hidden(hidden_id))
+    );
+    assert_eq!(
+        named_by_id()
+            .bind(client, &visible_id)
+            .try_map(visible)
+            .one()
+            .unwrap(),
+        "stuff"
+    );
+    assert_eq!(
+        named_by_id().bind(client, &-1).try_map(visible).one(),
+        Err(MapError::Db)
+    );
+
+    // Rows obtained elsewhere can be decoded by column name
+    let row = client
+        .query_one(
+            "SELECT show, name, price, id FROM named WHERE id = $1",
+            &[&hidden_id],
+        )
+        .unwrap();
+    assert_eq!(
+        Named::from_row(&row).unwrap(),
+        Named {
+            id: hidden_id,
+            name: "secret".into(),
+            price: Some(42.0),
+            show: false
+        }
+    );
+    assert_eq!(
+        NamedBorrowed::from_row_borrowed(&row).unwrap().name,
+        "secret"
+    );
+    let row = client
+        .query_one("SELECT id, name FROM named WHERE id = $1", &[&hidden_id])
+        .unwrap();
+    assert!(Named::from_row(&row).is_err());
+
     new_named_complex()
         .params(
             client,
@@ -295,6 +521,273 @@ pub fn test_named(client: &mut Client) {
             }
         ],
     );
+
+    // Owned params can be built on another thread and bound later
+    let (params, complex) = std::thread::spawn(|| {
+        (
+            NamedParamsOwned {
+                name: "owned".to_string(),
+                price: Some(21.0),
+            },
+            NamedComplexParamsOwned {
+                named: NamedComposite {
+                    wow: Some("Hello owned world".to_string()),
+                    such_cool: Some(3),
+                },
+                named_with_dot: None,
+            },
+        )
+    })
+    .join()
+    .unwrap();
+    let owned_id = new_named_visible()
+        .params(client, &params)
+        .one()
+        .unwrap()
+        .id;
+    assert_eq!(
+        named_by_id().bind(client, &owned_id).one().unwrap(),
+        Named {
+            id: owned_id,
+            name: "owned".into(),
+            price: Some(21.0),
+            show: true
+        }
+    );
+    new_named_complex().params(client, &complex).unwrap();
+    assert_eq!(
+        named_complex().bind(client).all().unwrap().pop().unwrap(),
+        NamedComplex {
+            named: complex.named,
+            named_with_dot: None,
+        }
+    );
+}
+
+// Test attributes declared in annotations
+pub fn test_attributes(client: &mut Client) {
+    let ids: HashSet<NamedId> = named_ids()
+        .bind(client)
+        .all()
+        .unwrap()
+        .into_iter()
+        .collect();
+    let hashable: HashSet<NamedHashable> = named_hashable()
+        .bind(client)
+        .all()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(ids.len(), hashable.len());
+    assert!(hashable.iter().all(|it| ids.contains(&NamedId {
+        id: it.id.unwrap(),
+        show: it.is_shown
+    })));
+    let hashable = serde_json::to_value(hashable.iter().next().unwrap()).unwrap();
+    assert!(hashable.get("isShown").is_some());
+
+    let shout = named_shout().bind(client).all().unwrap();
+    let shout = serde_json::to_value(&shout[0]).unwrap();
+    assert!(shout.get("NAME").is_some() && shout.get("SHOW").is_some());
+
+    #[allow(deprecated)]
+    let deprecated = crate::cornucopia::queries::attributes::sync::named_deprecated()
+        .bind(client)
+        .all()
+        .unwrap();
+    assert_eq!(deprecated.len(), ids.len());
+    assert_eq!(named_crate().bind(client).all().unwrap().len(), ids.len());
+}
+
+// Test direct fns generated from cardinality markers
+pub fn test_cardinality(client: &mut Client) {
+    let first = cardinality_insert()
+        .bind(client, &"cardinality", &4.2)
+        .unwrap();
+    let second = cardinality_insert()
+        .bind(client, &"cardinality", &2.4)
+        .unwrap();
+    assert_ne!(first, second);
+    assert_eq!(
+        cardinality_one().bind(client, &first).unwrap(),
+        NamedPrice {
+            name: "cardinality".into(),
+            price: Some(4.2)
+        }
+    );
+    assert_eq!(
+        cardinality_opt().bind(client, &second).unwrap(),
+        Some(NamedPrice {
+            name: "cardinality".into(),
+            price: Some(2.4)
+        })
+    );
+    assert_eq!(cardinality_opt().bind(client, &-1).unwrap(), None);
+    assert!(cardinality_one().bind(client, &-1).is_err());
+    assert_eq!(
+        cardinality_many().bind(client, &"cardinality").unwrap(),
+        vec!["cardinality".to_string(), "cardinality".to_string()]
+    );
+    assert_eq!(
+        cardinality_exec_rows()
+            .bind(client, &"cardinality")
+            .unwrap(),
+        2
+    );
+
+    // Shared types are generated once and used by every module
+    new_named_shared()
+        .params(
+            client,
+            &NamedInsert {
+                name: "cardinality",
+                price: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        cardinality_summary().bind(client, &"cardinality").unwrap(),
+        vec![
+            NamedSummary {
+                name: "cardinality".into(),
+                price: Some(4.2)
+            },
+            NamedSummary {
+                name: "cardinality".into(),
+                price: Some(2.4)
+            },
+            NamedSummary {
+                name: "cardinality".into(),
+                price: None
+            }
+        ]
+    );
+    assert_eq!(
+        named_summary().bind(client, &first).one().unwrap(),
+        NamedSummary {
+            name: "cardinality".into(),
+            price: Some(4.2)
+        }
+    );
+}
+
+// Test derives configured for each kind of generated item
+pub fn test_derives() {
+    // Rows and params can be deserialized
+    let named: Named =
+        serde_json::from_str(r#"{"id":1,"name":"derive","price":null,"show":true}"#).unwrap();
+    assert_eq!(named.name, "derive");
+    let params: NamedParams<String> =
+        serde_json::from_str(r#"{"name":"derive","price":1.5}"#).unwrap();
+    assert_eq!(params.price, Some(1.5));
+
+    // Rows without float are hashable
+    let complex = NamedComplex {
+        named: NamedComposite {
+            wow: Some("derive".into()),
+            such_cool: Some(1),
+        },
+        named_with_dot: None,
+    };
+    let set: HashSet<_> = [complex.clone(), complex].into_iter().collect();
+    assert_eq!(set.len(), 1);
+
+    // Enums are ordered by variant declaration
+    let mut characters = vec![
+        SpongebobCharacter::Squidward,
+        SpongebobCharacter::Bob,
+        SpongebobCharacter::Patrick,
+    ];
+    characters.sort();
+    assert_eq!(
+        characters,
+        [
+            SpongebobCharacter::Bob,
+            SpongebobCharacter::Patrick,
+            SpongebobCharacter::Squidward
+        ]
+    );
+}
+
+// Test rows mapped onto user-defined structs
+pub fn test_external(client: &mut Client) {
+    let id = new_named_visible()
+        .bind(client, &"external", &Some(1.5))
+        .one()
+        .unwrap()
+        .id;
+    let product = Product {
+        id,
+        name: "external".into(),
+        price: Some(1.5),
+    };
+    assert_eq!(
+        external_named_by_id().bind(client, &id).unwrap(),
+        Some(product.clone())
+    );
+    assert_eq!(external_named_by_id().bind(client, &-1).unwrap(), None);
+    assert!(external_named()
+        .bind(client)
+        .all()
+        .unwrap()
+        .contains(&product));
+    assert_eq!(
+        external_named()
+            .bind(client)
+            .map(|it| it.name)
+            .iter()
+            .unwrap()
+            .last()
+            .unwrap()
+            .unwrap(),
+        "external"
+    );
+}
+
+// Test execute queries with an expected number of affected rows
+pub fn test_expect(client: &mut Client) {
+    let first = cardinality_insert().bind(client, &"expect", &4.2).unwrap();
+    let second = cardinality_insert().bind(client, &"expect", &2.4).unwrap();
+    assert_eq!(
+        expect_rename()
+            .bind(client, &"expect_renamed", &first)
+            .unwrap(),
+        1
+    );
+    assert!(matches!(
+        expect_rename().bind(client, &"expect_renamed", &-1),
+        Err(RowCountError::Unexpected {
+            expected: 1,
+            actual: 0
+        })
+    ));
+    cardinality_insert().bind(client, &"expect", &4.2).unwrap();
+
+    // Mismatching statement is rolled back to its savepoint
+    let mut transaction = client.transaction().unwrap();
+    assert!(matches!(
+        expect_rename_all().bind(&mut transaction, &"expect_renamed", &"expect"),
+        Err(RowCountError::Unexpected {
+            expected: 1,
+            actual: 2
+        })
+    ));
+    assert_eq!(
+        cardinality_many()
+            .bind(&mut transaction, &"expect")
+            .unwrap()
+            .len(),
+        2
+    );
+    transaction.commit().unwrap();
+    // Savepoints require a transaction
+    assert!(matches!(
+        expect_rename_all().bind(client, &"expect_renamed", &"expect"),
+        Err(RowCountError::NoTransaction)
+    ));
+
+    assert_eq!(expect_delete().bind(client, &second).unwrap(), 1);
+    assert!(expect_delete().bind(client, &second).is_err());
 }
 
 // Test we correctly implement borrowed version and copy derive
@@ -568,5 +1061,244 @@ pub fn test_keyword_escaping(client: &mut Client) {
         r#enum: SyntaxEnum::r#box,
     };
     tricky_sql10().params(client, &params).unwrap();
+    let params = TrickySql11Params {
+        r#async: SyntaxComposite { r#async: 34 },
+        r#enum: SyntaxEnum::r#box,
+    };
+    tricky_sql11().params(client, &params).unwrap();
+    let params = TrickySql12Params {
+        r#async: SyntaxComposite { r#async: 34 },
+        r#enum: SyntaxEnum::r#box,
+    };
+    tricky_sql12().params(client, &params).unwrap();
+    // `@` operators written without spaces are not parameters
+    assert_eq!(
+        tricky_sql13().bind(client, &"a word").one().unwrap(),
+        TrickySql13 {
+            matches: true,
+            contains: true
+        }
+    );
     r#typeof().bind(client).all().unwrap();
 }
+
+// Test repository interfaces, implemented by a client or a mock
+pub fn test_repository(client: &mut Client) {
+    use crate::cornucopia::queries::params::{sync::ClientRepository, sync::Repository};
+
+    fn book_names<R: Repository>(repository: &mut R) -> Result<Vec<String>, R::Error> {
+        repository.insert_book(None, "Ubik".into())?;
+        Ok(repository
+            .select_book()?
+            .into_iter()
+            .map(|it| it.name)
+            .collect())
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Unavailable;
+
+    // Only records inserted books, other queries return nothing
+    #[derive(Default)]
+    struct Mock {
+        books: Vec<SelectBook>,
+        unavailable: bool,
+    }
+    impl Repository for Mock {
+        type Error = Unavailable;
+        fn insert_book(
+            &mut self,
+            author: Option<String>,
+            name: String,
+        ) -> Result<u64, Unavailable> {
+            if self.unavailable {
+                return Err(Unavailable);
+            }
+            self.books.push(SelectBook { author, name });
+            Ok(1)
+        }
+        fn select_book(&mut self) -> Result<Vec<SelectBook>, Unavailable> {
+            Ok(self.books.clone())
+        }
+        fn find_books(&mut self, _: Vec<String>) -> Result<Vec<FindBooks>, Unavailable> {
+            Ok(Default::default())
+        }
+        fn params_use_twice(&mut self, _: String) -> Result<u64, Unavailable> {
+            Ok(Default::default())
+        }
+        fn params_order(&mut self, _: i32, _: i32) -> Result<u64, Unavailable> {
+            Ok(Default::default())
+        }
+        fn params_method_names(
+            &mut self,
+            _: String,
+            _: String,
+            _: String,
+        ) -> Result<u64, Unavailable> {
+            Ok(Default::default())
+        }
+        fn insert_book_quoted(&mut self, _: Option<String>, _: String) -> Result<u64, Unavailable> {
+            Ok(Default::default())
+        }
+        fn select_book_positional(
+            &mut self,
+            _: String,
+            _: Option<String>,
+        ) -> Result<Vec<SelectBookPositional>, Unavailable> {
+            Ok(Default::default())
+        }
+        fn select_book_columns(
+            &mut self,
+            _: Vec<String>,
+        ) -> Result<Vec<SelectBookColumns>, Unavailable> {
+            Ok(Default::default())
+        }
+        fn select_book_sigil(
+            &mut self,
+            _: Vec<String>,
+        ) -> Result<Vec<SelectBookSigil>, Unavailable> {
+            Ok(Default::default())
+        }
+    }
+
+    assert_eq!(book_names(&mut Mock::default()).unwrap(), ["Ubik"]);
+    let mut unavailable = Mock {
+        unavailable: true,
+        ..Default::default()
+    };
+    assert_eq!(book_names(&mut unavailable), Err(Unavailable));
+    let mut transaction = client.transaction().unwrap();
+    let mut repository = ClientRepository::new(&mut transaction);
+    assert_eq!(book_names(&mut repository).unwrap().last().unwrap(), "Ubik");
+    assert_eq!(
+        repository.select_book_sigil(vec!["Ubik".into()]).unwrap(),
+        [SelectBookSigil {
+            author: None,
+            name: "Ubik".into()
+        }]
+    );
+    transaction.rollback().unwrap();
+}
+
+// Test spans opened by the `tracing` feature
+pub fn test_tracing(client: &mut Client) {
+    use std::sync::{Arc, Mutex};
+    use tracing::{
+        field::{Field, Visit},
+        span, Event, Metadata, Subscriber,
+    };
+
+    type Fields = Vec<(&'static str, String)>;
+
+    // Records the fields of every span
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<Fields>>>);
+    struct Visitor<'a>(&'a mut Fields);
+    impl Visit for Visitor<'_> {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.push((field.name(), value.to_string()));
+        }
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0.push((field.name(), format!("{value:?}")));
+        }
+    }
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata) -> bool {
+            true
+        }
+        fn new_span(&self, span: &span::Attributes) -> span::Id {
+            let mut spans = self.0.lock().unwrap();
+            let mut fields = Vec::new();
+            span.record(&mut Visitor(&mut fields));
+            spans.push(fields);
+            span::Id::from_u64(spans.len() as u64)
+        }
+        fn record(&self, span: &span::Id, values: &span::Record) {
+            let mut spans = self.0.lock().unwrap();
+            values.record(&mut Visitor(&mut spans[span.into_u64() as usize - 1]));
+        }
+        fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+        fn event(&self, _: &Event) {}
+        fn enter(&self, _: &span::Id) {}
+        fn exit(&self, _: &span::Id) {}
+    }
+
+    let recorder = Recorder::default();
+    let nb_rows = tracing::subscriber::with_default(recorder.clone(), || {
+        let nb_rows = named().bind(client).all().unwrap().len();
+        let mut unnamed = cornucopia_sync::private::Stmt::new("SELECT 1");
+        cornucopia_sync::private::execute(client, &mut unnamed, &[]).unwrap();
+        nb_rows
+    });
+    let spans = recorder.0.lock().unwrap();
+    let field = |fields: &Fields, name: &str| {
+        fields
+            .iter()
+            .find(|(field, _)| *field == name)
+            .map(|(_, value)| value.clone())
+    };
+    let operations: Vec<_> = spans
+        .iter()
+        .filter_map(|fields| field(fields, "db.operation"))
+        .collect();
+    assert_eq!(operations, ["all", "prepare", "execute", "prepare"]);
+    let all = &spans[0];
+    assert_eq!(field(all, "otel.name").unwrap(), "named.named");
+    assert_eq!(field(all, "otel.kind").unwrap(), "client");
+    assert_eq!(field(all, "db.system").unwrap(), "postgresql");
+    assert!(field(all, "db.statement").unwrap().starts_with("SELECT"));
+    assert_eq!(
+        field(all, "db.response.returned_rows").unwrap(),
+        nb_rows.to_string()
+    );
+    // Unnamed statements are named by their SQL text
+    assert_eq!(field(&spans[2], "otel.name").unwrap(), "SELECT 1");
+}
+
+pub fn test_observer(client: &mut Client) {
+    use cornucopia_sync::{set_observer, QueryInfo, QueryObserver, QueryOutcome};
+    use std::sync::{Arc, Mutex};
+
+    // The metrics implementation is a valid observer
+    let _: &dyn QueryObserver = &cornucopia_async::MetricsObserver;
+
+    type Call = (&'static str, &'static str, usize, Option<u64>, bool);
+
+    // Records every observed call
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<Call>>>);
+    impl QueryObserver for Recorder {
+        fn after(&self, info: &QueryInfo, outcome: &QueryOutcome) {
+            self.0.lock().unwrap().push((
+                info.name,
+                info.method,
+                info.nb_params,
+                outcome.rows,
+                outcome.error.is_some(),
+            ));
+        }
+    }
+
+    let recorder = Recorder::default();
+    assert!(set_observer(recorder.clone()).is_ok());
+    assert!(set_observer(Recorder::default()).is_err());
+
+    let nb_rows = named().bind(client).all().unwrap().len() as u64;
+    assert!(named_by_id().bind(client, &-1).one().is_err());
+    named_by_id().bind(client, &-1).opt().unwrap();
+    let mut unnamed = cornucopia_sync::private::Stmt::new("SELECT 1");
+    cornucopia_sync::private::execute(client, &mut unnamed, &[]).unwrap();
+    assert_eq!(
+        recorder.0.lock().unwrap().as_slice(),
+        [
+            ("named.named", "prepare", 0, None, false),
+            ("named.named", "all", 0, Some(nb_rows), false),
+            ("named.named_by_id", "prepare", 0, None, false),
+            ("named.named_by_id", "one", 1, None, true),
+            ("named.named_by_id", "prepare", 0, None, false),
+            ("named.named_by_id", "opt", 1, Some(0), false),
+            ("SELECT 1", "prepare", 0, None, false),
+            ("SELECT 1", "execute", 0, Some(1), false),
+        ]
+    );
+}
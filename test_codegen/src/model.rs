@@ -0,0 +1,7 @@
+/// A user-defined struct mapped from query rows
+#[derive(Debug, Clone, PartialEq)]
+pub struct Product {
+    pub id: i32,
+    pub name: String,
+    pub price: Option<f64>,
+}
@@ -0,0 +1,6 @@
+// Typestate builders only allow executing queries once every required param is set
+#[test]
+fn builder() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
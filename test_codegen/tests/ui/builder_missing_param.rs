@@ -0,0 +1,17 @@
+#[path = "../../src/cornucopia.rs"]
+mod cornucopia;
+#[path = "../../src/model.rs"]
+mod model;
+
+use cornucopia::queries::params::sync::insert_book;
+
+// `name` is required, only `author` is nullable
+fn insert_author(client: &mut postgres::Client) {
+    insert_book()
+        .builder()
+        .author(&Some("Bram Stoker"))
+        .execute(client)
+        .unwrap();
+}
+
+fn main() {}
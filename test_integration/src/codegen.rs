@@ -3,13 +3,14 @@ use crate::{
     utils::{reset_db, rustfmt_file, rustfmt_string},
 };
 
-use cornucopia::{CodegenSettings, Error};
+use cornucopia::{CloneTarget, CodegenSettings, Error};
 use owo_colors::OwoColorize;
 use std::{env::set_current_dir, process::Command};
 
 // Run codegen test, return true if all test are successful
 pub(crate) fn run_codegen_test(
     client: &mut postgres::Client,
+    url: &str,
     apply: bool,
 ) -> Result<bool, Box<dyn std::error::Error>> {
     let mut successful = true;
@@ -29,30 +30,37 @@ pub(crate) fn run_codegen_test(
             // Load schema
             cornucopia::load_schema(client, &["schema.sql"])?;
 
+            let mut generate = |destination| {
+                if test.clone {
+                    cornucopia::generate_cloned(
+                        &test.queries_path,
+                        url,
+                        destination,
+                        CloneTarget::Scratch(url),
+                        CodegenSettings::from(&test),
+                    )
+                } else {
+                    cornucopia::generate_live(
+                        client,
+                        &test.queries_path,
+                        destination,
+                        CodegenSettings::from(&test),
+                    )
+                }
+            };
+
             // If `--apply`, then the code will be regenerated.
             // Otherwise, it is only checked.
             if apply {
                 // Generate
-                cornucopia::generate_live(
-                    client,
-                    &test.queries_path,
-                    Some(&test.destination),
-                    CodegenSettings::from(&test),
-                )
-                .map_err(Error::report)?;
+                generate(Some(&test.destination)).map_err(Error::report)?;
                 // Format the generated file
                 rustfmt_file(&test.destination);
             } else {
                 // Get currently checked-in generate file
                 let old_codegen = std::fs::read_to_string(&test.destination).unwrap();
                 // Generate new file
-                let new_codegen = cornucopia::generate_live(
-                    client,
-                    &test.queries_path,
-                    None,
-                    CodegenSettings::from(&test),
-                )
-                .map_err(Error::report)?;
+                let new_codegen = generate(None).map_err(Error::report)?;
                 // Format the generated code string by piping to rustfmt
                 let new_codegen_formatted = rustfmt_string(&new_codegen);
 
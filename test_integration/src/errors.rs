@@ -1,5 +1,6 @@
 use cornucopia::{CodegenSettings, Error};
 use owo_colors::OwoColorize;
+use std::path::Path;
 
 use crate::{
     fixtures::{ErrorTest, TestSuite},
@@ -37,15 +38,28 @@ pub(crate) fn run_errors_test(
                 .concat(),
             )?;
 
+            // Generate migrations files
+            std::fs::create_dir("migrations")?;
+            for (name, migration) in &test.migrations {
+                std::fs::write(Path::new("migrations").join(name), migration)?;
+            }
+
             // Generate queries files
             std::fs::create_dir("queries")?;
             std::fs::write(
                 "queries/test.sql",
                 test.query.as_deref().unwrap_or_default(),
             )?;
+            if let Some(shared) = &test.shared {
+                std::fs::write("queries/_types.sql", shared)?;
+            }
+            for (name, module) in &test.modules {
+                std::fs::write(Path::new("queries").join(name), module)?;
+            }
 
             // Run codegen
-            let result = cornucopia::load_schema(client, &["schema.sql"])
+            let result = cornucopia::load_extensions(client, &test.extensions)
+                .and_then(|_| cornucopia::load_schema(client, &["schema.sql", "migrations"]))
                 .map_err(Error::from)
                 .and_then(|_| {
                     cornucopia::generate_live(
@@ -1,9 +1,10 @@
 use std::{
+    collections::BTreeMap,
     error::Error,
     path::{Path, PathBuf},
 };
 
-use cornucopia::CodegenSettings;
+use cornucopia::{CodegenSettings, Derives};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
@@ -60,9 +61,31 @@ pub(crate) struct CodegenTest {
     #[serde(default)]
     pub(crate) derive_ser: bool,
     #[serde(default)]
+    pub(crate) derives: CodegenDerives,
+    #[serde(default)]
+    pub(crate) builder: bool,
+    #[serde(default)]
+    pub(crate) repository: bool,
+    /// Generate against a schema-only copy of the database instead
+    #[serde(default)]
+    pub(crate) clone: bool,
+    #[serde(default)]
     pub(crate) run: bool,
 }
 
+/// Additional derives of a codegen test case
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct CodegenDerives {
+    #[serde(default)]
+    pub(crate) rows: Vec<String>,
+    #[serde(default)]
+    pub(crate) params: Vec<String>,
+    #[serde(default)]
+    pub(crate) enums: Vec<String>,
+    #[serde(default)]
+    pub(crate) composites: Vec<String>,
+}
+
 fn default_queries_path() -> PathBuf {
     PathBuf::from("queries/")
 }
@@ -77,6 +100,14 @@ impl From<&CodegenTest> for CodegenSettings {
             gen_async: codegen_test.r#async || !codegen_test.sync,
             gen_sync: codegen_test.sync,
             derive_ser: codegen_test.derive_ser,
+            derives: Derives {
+                rows: codegen_test.derives.rows.clone(),
+                params: codegen_test.derives.params.clone(),
+                enums: codegen_test.derives.enums.clone(),
+                composites: codegen_test.derives.composites.clone(),
+            },
+            gen_builder: codegen_test.builder,
+            gen_repository: codegen_test.repository,
         }
     }
 }
@@ -86,7 +117,18 @@ impl From<&CodegenTest> for CodegenSettings {
 pub(crate) struct ErrorTest {
     pub(crate) name: String,
     pub(crate) query: Option<String>,
+    /// Content of the shared types module
+    pub(crate) shared: Option<String>,
+    /// Other query modules by file name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) modules: BTreeMap<String, String>,
+    /// Extensions created before loading the schema
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) extensions: Vec<String>,
     pub(crate) schema: Option<String>,
+    /// Migrations files by name, loaded after the schema
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) migrations: BTreeMap<String, String>,
     pub(crate) error: String,
 }
 
@@ -96,6 +138,9 @@ impl From<&ErrorTest> for CodegenSettings {
             derive_ser: false,
             gen_async: false,
             gen_sync: true,
+            derives: Derives::default(),
+            gen_builder: false,
+            gen_repository: false,
         }
     }
 }
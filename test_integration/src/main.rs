@@ -1,8 +1,11 @@
-use std::{fmt::Display, process::ExitCode};
+use std::{
+    fmt::Display,
+    process::{Command, ExitCode},
+};
 
 use crate::{codegen::run_codegen_test, errors::run_errors_test};
 use clap::Parser;
-use cornucopia::container;
+use cornucopia::{container, ContainerConfig, Engine};
 
 mod codegen;
 mod errors;
@@ -40,18 +43,50 @@ fn test(
         podman,
     }: Args,
 ) -> bool {
-    // Start by removing previous container if it was left open
-    container::cleanup(podman).ok();
-    container::setup(podman).unwrap();
+    let config = ContainerConfig {
+        engine: if podman {
+            Engine::Podman
+        } else {
+            Engine::Docker
+        },
+        image: pg_dump_image(),
+        // The generated code and examples connect to this port
+        port: Some(5435),
+        ..ContainerConfig::default()
+    };
+    let container = container::setup(&config).unwrap();
+    let url = format!(
+        "postgresql://{}:{}@{}:{}/{}",
+        container.user(),
+        container.password(),
+        container.host(),
+        container.port(),
+        container.database()
+    );
     let successful = std::panic::catch_unwind(|| {
-        let mut client = cornucopia::conn::cornucopia_conn().unwrap();
+        let mut client = cornucopia::conn::cornucopia_conn(&container).unwrap();
         display(run_errors_test(&mut client, apply_errors)).unwrap()
-            && display(run_codegen_test(&mut client, apply_codegen)).unwrap()
+            && display(run_codegen_test(&mut client, &url, apply_codegen)).unwrap()
     });
-    container::cleanup(podman).unwrap();
+    container.cleanup().unwrap();
     successful.unwrap()
 }
 
+/// Image of the host's `pg_dump` major version, which dumps schema copies and can't be older
+/// than the server
+fn pg_dump_image() -> String {
+    let output = Command::new("pg_dump").arg("--version").output();
+    let version = output.as_ref().map_or(Default::default(), |it| {
+        String::from_utf8_lossy(&it.stdout).into_owned()
+    });
+    let major = version
+        .split_whitespace()
+        .nth(2)
+        .and_then(|version| version.split('.').next())
+        .filter(|major| major.chars().all(|c| c.is_ascii_digit()));
+    format!("docker.io/library/postgres:{}", major.unwrap_or("latest"))
+}
+
 /// Main entry point
 fn main() -> ExitCode {
     let args = Args::parse();
@@ -64,6 +99,10 @@ fn main() -> ExitCode {
 
 #[cfg(test)]
 mod test {
+    use std::process::Command;
+
+    use cornucopia::{container, ContainerConfig, Engine};
+
     use crate::test;
 
     #[test]
@@ -74,4 +113,281 @@ mod test {
             podman: false
         }))
     }
+
+    /// `initdb` must be on `PATH`, and refuses to run as root
+    fn local_engine_available() -> bool {
+        let installed = Command::new("initdb")
+            .arg("--version")
+            .output()
+            .map_or(false, |it| it.status.success());
+        let root = Command::new("id").arg("-u").output().map_or(false, |it| {
+            String::from_utf8_lossy(&it.stdout).trim() == "0"
+        });
+        installed && !root
+    }
+
+    #[test]
+    fn local_engine() {
+        if !local_engine_available() {
+            eprintln!("skipped: no usable `initdb` on PATH");
+            return;
+        }
+        let config = ContainerConfig {
+            engine: Engine::Local { bin_dir: None },
+            ..ContainerConfig::default()
+        };
+        let container = container::setup(&config).unwrap();
+        let dir = std::env::temp_dir().join(container.name());
+        assert!(dir.exists());
+        // The cluster is private and its password isn't left on disk
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&dir).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700);
+        }
+        assert!(!dir.join("pwfile").exists());
+        // Another cluster can't take over an existing directory
+        let taken = ContainerConfig {
+            name: Some(container.name().to_owned()),
+            ..config.clone()
+        };
+        assert!(container::setup(&taken).is_err());
+        let mut client = cornucopia::conn::cornucopia_conn(&container).unwrap();
+        let one: i32 = client.query_one("SELECT 1", &[]).unwrap().get(0);
+        assert_eq!(one, 1);
+        drop(client);
+        // Dropping the container stops the cluster and removes its directory
+        drop(container);
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn engine_port() {
+        // The engine publishes the container on a port of its choice
+        let container = container::setup(&ContainerConfig::default()).unwrap();
+        assert_ne!(container.port(), 0);
+        let mut client = cornucopia::conn::cornucopia_conn(&container).unwrap();
+        let one: i32 = client.query_one("SELECT 1", &[]).unwrap().get(0);
+        assert_eq!(one, 1);
+        drop(client);
+        container.cleanup().unwrap();
+    }
+
+    #[test]
+    fn keep_container() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let config = ContainerConfig {
+            name: Some(String::from("cornucopia_keep_test")),
+            port: Some(port),
+            keep: true,
+            ..ContainerConfig::default()
+        };
+        let has_leftover = |container: &container::Container| {
+            let mut client = cornucopia::conn::cornucopia_conn(container).unwrap();
+            let leftover: bool = client
+                .query_one("SELECT to_regclass('leftover') IS NOT NULL", &[])
+                .unwrap()
+                .get(0);
+            client.batch_execute("CREATE TABLE leftover ()").unwrap();
+            leftover
+        };
+
+        let container = container::setup(&config).unwrap();
+        assert!(!has_leftover(&container));
+        // Concurrent runs get their own database
+        let concurrent = container::setup(&config).unwrap();
+        assert_ne!(container.database(), concurrent.database());
+        assert!(!has_leftover(&concurrent));
+        concurrent.cleanup().unwrap();
+        container.cleanup().unwrap();
+        // The running container is reused with a fresh database
+        let container = container::setup(&config).unwrap();
+        assert!(!has_leftover(&container));
+        container.cleanup().unwrap();
+        // The stopped container is started again with a fresh database
+        let engine = match config.engine {
+            Engine::Podman => "podman",
+            _ => "docker",
+        };
+        Command::new(engine)
+            .args(["stop", "cornucopia_keep_test"])
+            .output()
+            .unwrap();
+        let container = container::setup(&config).unwrap();
+        assert!(!has_leftover(&container));
+        container.cleanup().unwrap();
+        // The container is recreated when its configuration changed
+        let changed = ContainerConfig {
+            password: String::from("changed"),
+            ..config
+        };
+        let container = container::setup(&changed).unwrap();
+        assert!(!has_leftover(&container));
+        container.remove().unwrap();
+    }
+
+    #[cfg(feature = "native-tls")]
+    #[test]
+    fn tls() {
+        use cornucopia::{CloneTarget, CodegenSettings};
+
+        let config = ContainerConfig {
+            image: crate::pg_dump_image(),
+            ..ContainerConfig::default()
+        };
+        let container = container::setup(&config).unwrap();
+        let mut client = cornucopia::conn::cornucopia_conn(&container).unwrap();
+        // Serve the self-signed certificate shipped with the image
+        for stmt in [
+            "ALTER SYSTEM SET ssl_cert_file = '/etc/ssl/certs/ssl-cert-snakeoil.pem'",
+            "ALTER SYSTEM SET ssl_key_file = '/etc/ssl/private/ssl-cert-snakeoil.key'",
+            "ALTER SYSTEM SET ssl = on",
+            "SELECT pg_reload_conf()",
+            "CREATE TABLE tls (id INT)",
+        ] {
+            client.batch_execute(stmt).unwrap();
+        }
+        let cert: String = client
+            .query_one(
+                "SELECT pg_read_file('/etc/ssl/certs/ssl-cert-snakeoil.pem')",
+                &[],
+            )
+            .unwrap()
+            .get(0);
+        // The configuration is reloaded asynchronously
+        for _ in 0..50 {
+            let mut client = cornucopia::conn::cornucopia_conn(&container).unwrap();
+            let ssl: String = client.query_one("SHOW ssl", &[]).unwrap().get(0);
+            if ssl == "on" {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let root_cert = dir.path().join("root.crt");
+        std::fs::write(&root_cert, cert).unwrap();
+        let queries = dir.path().join("queries");
+        std::fs::create_dir(&queries).unwrap();
+        std::fs::write(queries.join("tls.sql"), "--! ids\nSELECT id FROM tls;").unwrap();
+        // Connections to the live database, the scratch database and `pg_dump` all use TLS
+        let generate = |params: &str| {
+            let url = format!(
+                "postgresql://{}:{}@{}:{}/{}?{params}",
+                container.user(),
+                container.password(),
+                container.host(),
+                container.port(),
+                container.database()
+            );
+            cornucopia::generate_cloned(
+                &queries,
+                &url,
+                None,
+                CloneTarget::Scratch(&url),
+                CodegenSettings::default(),
+            )
+        };
+        let root_cert = root_cert.display();
+
+        generate("sslmode=require").unwrap();
+        generate(&format!("sslmode=verify-ca&sslrootcert={root_cert}")).unwrap();
+        // `require` verifies the certificate when a root certificate is given
+        generate(&format!("sslmode=require&sslrootcert={root_cert}")).unwrap();
+        // The self-signed certificate isn't trusted without its root certificate
+        assert!(generate("sslmode=verify-ca").is_err());
+
+        // Clients authenticate with a certificate signed by a CA trusted by the server
+        let openssl = |args: &str| {
+            let status = Command::new("openssl")
+                .args(args.split(' '))
+                .current_dir(dir.path())
+                .stderr(std::process::Stdio::null())
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+        openssl(
+            "req -x509 -newkey rsa:2048 -nodes -days 1 -subj /CN=ca -keyout ca.key -out ca.crt",
+        );
+        openssl(
+            "req -newkey rsa:2048 -nodes -subj /CN=cert_user -keyout client.key -out client.csr",
+        );
+        openssl("x509 -req -days 1 -in client.csr -CA ca.crt -CAkey ca.key -CAcreateserial -out client.crt");
+        openssl("pkey -traditional -in client.key -out client_rsa.key");
+        // libpq refuses keys readable by other users
+        for key in ["client.key", "client_rsa.key"] {
+            use std::os::unix::fs::PermissionsExt;
+            let permissions = std::fs::Permissions::from_mode(0o600);
+            std::fs::set_permissions(dir.path().join(key), permissions).unwrap();
+        }
+        let hba_file: String = client.query_one("SHOW hba_file", &[]).unwrap().get(0);
+        let hba: String = client
+            .query_one("SELECT pg_read_file($1)", &[&hba_file])
+            .unwrap()
+            .get(0);
+        // Files are written by the server, which can't read the host's
+        let mut write_server_file = |content: &str, path: &str| {
+            let oid: u32 = client
+                .query_one(
+                    "SELECT lo_from_bytea(0, convert_to($1, 'UTF8'))",
+                    &[&content],
+                )
+                .unwrap()
+                .get(0);
+            client
+                .execute("SELECT lo_export($1, $2)", &[&oid, &path])
+                .unwrap();
+        };
+        let ca = std::fs::read_to_string(dir.path().join("ca.crt")).unwrap();
+        write_server_file(&ca, "client_ca.crt");
+        write_server_file(&format!("hostssl all cert_user all cert\n{hba}"), &hba_file);
+        for stmt in [
+            "ALTER SYSTEM SET ssl_ca_file = 'client_ca.crt'",
+            "CREATE ROLE cert_user LOGIN CREATEDB",
+            "GRANT SELECT ON tls TO cert_user",
+            "SELECT pg_reload_conf()",
+        ] {
+            client.batch_execute(stmt).unwrap();
+        }
+        let generate_cert = |key: &str| {
+            let url = format!(
+                "postgresql://cert_user@{}:{}/{}?sslmode=require&sslcert={}&sslkey={}",
+                container.host(),
+                container.port(),
+                container.database(),
+                dir.path().join("client.crt").display(),
+                dir.path().join(key).display(),
+            );
+            cornucopia::generate_cloned(
+                &queries,
+                &url,
+                None,
+                CloneTarget::Scratch(&url),
+                CodegenSettings::default(),
+            )
+            .map_err(|err| err.to_string())
+        };
+        // The configuration is reloaded asynchronously
+        let mut result = generate_cert("client.key");
+        for _ in 0..50 {
+            if result.is_ok() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            result = generate_cert("client.key");
+        }
+        result.unwrap();
+        // Traditional keys must be converted to PKCS#8
+        assert!(generate_cert("client_rsa.key")
+            .unwrap_err()
+            .contains("is a PKCS#1 RSA key"));
+        drop(client);
+        container.cleanup().unwrap();
+    }
 }